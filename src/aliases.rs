@@ -6,20 +6,66 @@ use std::{
 };
 
 use crate::error::Error;
+use crate::pool::{default_pool_size, ConnectionPool};
+use crate::schema::{create_database, ALIASES_SCHEMA};
 use chrono::NaiveDateTime;
-use rusqlite::{types::Value, Connection, OpenFlags};
+use rusqlite::{types::Value, OpenFlags, OptionalExtension};
 use tracing::trace;
 
+/// Version of the aliases database schema.
+static SCHEMA_VERSION: u32 = 1;
+
 /// Namespaces as stored in the database.
 ///
 /// The string values returned by the `Display` trait are the values stored in
-/// the database.
-#[derive(Debug, PartialEq, Eq, Clone)]
+/// the database.  Use `Namespace::normalize()` for mapping synonyms as used in
+/// identifiers (e.g., `refseq`) to these values and `canonical_name()` for the
+/// reverse direction.
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
 pub struct Namespace {
     pub value: String,
 }
 
+/// Known namespaces as stored in the database and their synonyms.
+///
+/// Synonyms are matched case-insensitively, the first one is the canonical name used
+/// in identifiers.
+static KNOWN_NAMESPACES: &[(&str, &[&str])] = &[
+    (Namespace::NCBI, &["refseq", "ncbi"]),
+    (Namespace::ENSEMBL, &["Ensembl"]),
+    (Namespace::LRG, &["LRG"]),
+    (Namespace::MD5, &["MD5"]),
+    (Namespace::SEGUID, &["SEGUID"]),
+    (Namespace::SHA1, &["SHA1"]),
+    (Namespace::VMC, &["VMC"]),
+    (Namespace::GRCH37, &["GRCh37"]),
+    (Namespace::GRCH38, &["GRCh38"]),
+];
+
 impl Namespace {
+    /// Namespace of RefSeq accessions, `refseq` in identifiers.
+    pub const NCBI: &'static str = "NCBI";
+    /// Namespace of Ensembl accessions.
+    pub const ENSEMBL: &'static str = "Ensembl";
+    /// Namespace of LRG accessions.
+    pub const LRG: &'static str = "LRG";
+    /// Namespace of hex-encoded MD5 digests.
+    pub const MD5: &'static str = "MD5";
+    /// Namespace of SEGUID digests.
+    pub const SEGUID: &'static str = "SEGUID";
+    /// Namespace of hex-encoded SHA-1 digests.
+    pub const SHA1: &'static str = "SHA1";
+    /// Namespace of `GS_`-prefixed `sha512t24u` digests.
+    pub const VMC: &'static str = "VMC";
+    /// Namespace of the sequence names of the GRCh37 assembly, e.g., `1` or `X`.
+    pub const GRCH37: &'static str = "GRCh37";
+    /// Namespace of the sequence names of the GRCh38 assembly, e.g., `1` or `X`.
+    pub const GRCH38: &'static str = "GRCh38";
+    /// Pseudo-namespace of `SQ.`-prefixed `seq_id`s, not stored in the database.
+    pub const GA4GH: &'static str = "ga4gh";
+    /// Pseudo-namespace of plain `seq_id`s, not stored in the database.
+    pub const SHA512T24U: &'static str = "sha512t24u";
+
     pub fn new(value: &str) -> Self {
         Self {
             value: value.to_string(),
@@ -29,6 +75,56 @@ impl Namespace {
     pub fn from(value: String) -> Self {
         Self { value }
     }
+
+    /// Create namespace from `value` as used in identifiers, mapping synonyms.
+    ///
+    /// Known namespaces are matched case-insensitively, e.g., `refseq` gives `NCBI`
+    /// and `md5` gives `MD5`.  Other values are kept as they are.
+    pub fn normalize(value: &str) -> Self {
+        match find_known_namespace(value) {
+            Some(name) => Self::new(name),
+            None => Self::new(value),
+        }
+    }
+
+    /// Whether `value` is a known namespace or one of its synonyms.
+    pub fn is_known(value: &str) -> bool {
+        find_known_namespace(value).is_some()
+    }
+
+    /// Return the name used in identifiers, e.g., `refseq` for `NCBI`.
+    pub fn canonical_name(&self) -> &str {
+        KNOWN_NAMESPACES
+            .iter()
+            .find(|(name, _)| *name == self.value)
+            .map(|(_, synonyms)| synonyms[0])
+            .unwrap_or(&self.value)
+    }
+
+    /// Whether this is one of the pseudo-namespaces for the `seq_id`.
+    pub fn is_seq_id(&self) -> bool {
+        self.value.eq_ignore_ascii_case(Self::GA4GH)
+            || self.value.eq_ignore_ascii_case(Self::SHA512T24U)
+    }
+}
+
+/// Return the database value of the known namespace `value`, matching synonyms.
+fn find_known_namespace(value: &str) -> Option<&'static str> {
+    KNOWN_NAMESPACES
+        .iter()
+        .find(|(name, synonyms)| {
+            name.eq_ignore_ascii_case(value)
+                || synonyms
+                    .iter()
+                    .any(|synonym| synonym.eq_ignore_ascii_case(value))
+        })
+        .map(|(name, _)| *name)
+}
+
+impl std::fmt::Display for Namespace {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.value)
+    }
 }
 
 impl std::ops::Deref for Namespace {
@@ -46,17 +142,86 @@ pub struct NamespacedAlias {
     pub alias: String,
 }
 
+/// Formats as `namespace:alias` identifier.
+impl std::fmt::Display for NamespacedAlias {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", *self.namespace, self.alias)
+    }
+}
+
+/// Prefix of `seq_id` in `ga4gh` identifiers.
+pub(crate) static GA4GH_PREFIX: &str = "SQ.";
+
+/// Return the `seq_id` given as `alias` in one of the `seq_id` pseudo-namespaces.
+pub(crate) fn seq_id_from_alias<'a>(namespace: &Namespace, alias: &'a str) -> Option<&'a str> {
+    if namespace.value.eq_ignore_ascii_case(Namespace::GA4GH) {
+        alias.strip_prefix(GA4GH_PREFIX)
+    } else if namespace.value.eq_ignore_ascii_case(Namespace::SHA512T24U) {
+        Some(alias)
+    } else {
+        None
+    }
+}
+
+/// Split `identifier` into namespace and alias at the first colon, if any.
+pub(crate) fn split_identifier(identifier: &str) -> (Option<&str>, &str) {
+    match identifier.split_once(':') {
+        Some((namespace, alias)) => (Some(namespace), alias),
+        None => (None, identifier),
+    }
+}
+
+/// Ordering of the results of `AliasDb::find()`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OrderBy {
+    /// Order by `seq_id`, then namespace and alias.
+    #[default]
+    SeqId,
+    /// Order by namespace and alias, then `seq_id`.
+    Alias,
+    /// Order by the time that the alias was added.
+    Added,
+    /// Order by the row ID, i.e., the insertion order.
+    SeqaliasId,
+}
+
+impl OrderBy {
+    /// Columns to order by.
+    fn columns(&self) -> &'static [&'static str] {
+        match self {
+            OrderBy::SeqId => &["seq_id", "namespace", "alias"],
+            OrderBy::Alias => &["namespace", "alias", "seq_id"],
+            OrderBy::Added => &["added", "seqalias_id"],
+            OrderBy::SeqaliasId => &["seqalias_id"],
+        }
+    }
+}
+
 /// Datastructure for a query to `AliasDb::find()`.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Query {
     /// Optionally, namespace to query within.
     pub namespace: Option<Namespace>,
     /// Optionally, an alias or pattern using `%` for wildcards.
     pub alias: Option<String>,
+    /// Optionally, a prefix of the alias, matched case-sensitively.
+    ///
+    /// Other than a `%` pattern in `alias`, this uses the index on the aliases.
+    pub alias_prefix: Option<String>,
     /// Optionally the precise seqid.
     pub seqid: Option<String>,
     /// Whether to return those with `is_current=1`.
     pub current_only: bool,
+    /// Optionally, only return aliases added at or before this time (UTC).
+    pub added_until: Option<NaiveDateTime>,
+    /// How to order the results.
+    pub order_by: OrderBy,
+    /// Whether to order in descending order.
+    pub descending: bool,
+    /// Optionally, the maximal number of records to return.
+    pub limit: Option<usize>,
+    /// Optionally, the number of records to skip.
+    pub offset: Option<usize>,
 }
 
 impl Default for Query {
@@ -64,14 +229,121 @@ impl Default for Query {
         Self {
             namespace: Default::default(),
             alias: Default::default(),
+            alias_prefix: Default::default(),
             seqid: Default::default(),
             current_only: true,
+            added_until: None,
+            order_by: Default::default(),
+            descending: false,
+            limit: None,
+            offset: None,
+        }
+    }
+}
+
+/// Policy for resolving aliases that refer to more than one sequence.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum ResolutionPolicy {
+    /// Fail with `Error::AliasDbResolutionAmbiguous`.
+    #[default]
+    Error,
+    /// Prefer matches in the first of the given namespaces that has any, fail if
+    /// the alias is still ambiguous.
+    NamespacePriority(Vec<String>),
+    /// Prefer the most recently added alias.
+    MostRecent,
+    /// Return all candidates.
+    All,
+}
+
+/// Options for resolving aliases with `AliasDb::resolve()`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ResolveOptions {
+    /// How to handle aliases that refer to more than one sequence.
+    pub policy: ResolutionPolicy,
+    /// Whether to resolve unversioned accessions such as `NM_000551` to their
+    /// highest version if there is no exact match.
+    pub versionless: bool,
+    /// Whether to also consider retired aliases, i.e., those with `is_current = 0`.
+    pub include_retired: bool,
+    /// Optionally, resolve aliases as they were at the given time (UTC).
+    ///
+    /// Only aliases added until then are considered and, of these, the most recently
+    /// added one for each namespace and alias, regardless of whether it has been
+    /// retired since.
+    pub as_of: Option<NaiveDateTime>,
+}
+
+impl ResolveOptions {
+    /// Base query for resolving aliases with these options.
+    fn query(&self) -> Query {
+        Query {
+            current_only: !self.include_retired && self.as_of.is_none(),
+            added_until: self.as_of,
+            ..Default::default()
+        }
+    }
+}
+
+/// Keep the most recently added record for each namespace and alias.
+///
+/// This gives the aliases that were current at the time of the latest `added` value
+/// among `records`.  The result is ordered by `seq_id`, namespace, and alias.
+fn most_recent_per_alias(mut records: Vec<AliasDbRecord>) -> Vec<AliasDbRecord> {
+    records.sort_by(|a, b| {
+        (&a.namespace.value, &a.alias, b.added, b.seqalias_id).cmp(&(
+            &b.namespace.value,
+            &b.alias,
+            a.added,
+            a.seqalias_id,
+        ))
+    });
+    records.dedup_by(|a, b| a.namespace == b.namespace && a.alias == b.alias);
+    records.sort_by(|a, b| {
+        (&a.seqid, &a.namespace.value, &a.alias).cmp(&(&b.seqid, &b.namespace.value, &b.alias))
+    });
+    records
+}
+
+/// A version of an accession as returned by `AliasDb::versions()`.
+#[derive(Debug, Clone)]
+pub struct AliasVersion {
+    /// The version number, e.g., `4` for `NM_000551.4`.
+    pub version: u32,
+    /// The alias record of the versioned accession.
+    pub record: AliasDbRecord,
+}
+
+/// Split `alias` into accession and numeric version, e.g., `NM_000551.4`.
+fn split_version(alias: &str) -> Option<(&str, u32)> {
+    let (accession, version) = alias.rsplit_once('.')?;
+    if accession.is_empty() || !version.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    version.parse().ok().map(|version| (accession, version))
+}
+
+/// Configuration of an `AliasDb`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AliasDbConfig {
+    /// Whether to open the database for writing.
+    pub writeable: bool,
+    /// Maximal number of read-only connections to the database, defaults to the
+    /// available parallelism.  Writeable databases use a single connection.
+    pub connection_pool_size: usize,
+}
+
+impl Default for AliasDbConfig {
+    fn default() -> Self {
+        Self {
+            writeable: false,
+            connection_pool_size: default_pool_size(),
         }
     }
 }
 
 /// Record as returned by `AliasDb::find()`.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct AliasDbRecord {
     pub seqalias_id: u64,
     pub seqid: String,
@@ -88,55 +360,168 @@ pub struct AliasDb {
     sr_root_dir: PathBuf,
     /// The name of the seqrepo instance.
     sr_instance: String,
-    /// Connection to the SQLite database.
-    conn: Arc<Mutex<Connection>>,
+    /// Configuration of the `AliasDb`.
+    config: AliasDbConfig,
+    /// Connections to the SQLite database.
+    pool: Arc<ConnectionPool>,
+    /// Aliases stored but not committed yet, as pairs of `seq_id` and alias.
+    pending: Mutex<Vec<(String, NamespacedAlias)>>,
 }
 
 impl AliasDb {
     pub fn new<P>(sr_root_dir: &P, sr_instance: &str) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+    {
+        Self::new_with_config(sr_root_dir, sr_instance, Default::default())
+    }
+
+    /// Open the `AliasDb` with the given configuration.
+    pub fn new_with_config<P>(
+        sr_root_dir: &P,
+        sr_instance: &str,
+        config: AliasDbConfig,
+    ) -> Result<Self, Error>
     where
         P: AsRef<Path>,
     {
         let sr_root_dir = PathBuf::from(sr_root_dir.as_ref());
         let sr_instance = sr_instance.to_string();
-        let conn = Self::new_connection(&sr_root_dir, &sr_instance)?;
+        let pool = Self::new_pool(&sr_root_dir, &sr_instance, &config)?;
 
         Ok(AliasDb {
             sr_root_dir,
             sr_instance,
-            conn,
+            config,
+            pool: Arc::new(pool),
+            pending: Default::default(),
         })
     }
 
-    fn new_connection(
+    /// Create a new, empty aliases database and open it.
+    ///
+    /// The instance directory must exist and must not contain an aliases database yet.
+    pub fn init<P>(sr_root_dir: &P, sr_instance: &str) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+    {
+        let db_path = sr_root_dir
+            .as_ref()
+            .join(sr_instance)
+            .join("aliases.sqlite3");
+        create_database(&db_path, ALIASES_SCHEMA, SCHEMA_VERSION)
+            .map_err(|e| Error::AliasDbExec(e.to_string()))?;
+        Self::new_with_config(
+            sr_root_dir,
+            sr_instance,
+            AliasDbConfig {
+                writeable: true,
+                ..Default::default()
+            },
+        )
+    }
+
+    fn new_pool(
         sr_root_dir: &Path,
         sr_instance: &str,
-    ) -> Result<Arc<Mutex<Connection>>, Error> {
+        config: &AliasDbConfig,
+    ) -> Result<ConnectionPool, Error> {
         let db_path = sr_root_dir.join(sr_instance).join("aliases.sqlite3");
-        let res = Connection::open_with_flags(
-            db_path,
-            OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
-        )
-        .map_err(|e| Error::AliasDbConnect(e.to_string()))?;
-        Ok(Arc::new(Mutex::new(res)))
+        let (flags, capacity) = if config.writeable {
+            (OpenFlags::SQLITE_OPEN_READ_WRITE, 1)
+        } else {
+            (
+                OpenFlags::SQLITE_OPEN_READ_ONLY,
+                config.connection_pool_size,
+            )
+        };
+        ConnectionPool::new(&db_path, flags, capacity, Error::AliasDbConnect)
+    }
+
+    /// Whether the database has been opened for writing.
+    pub fn writeable(&self) -> bool {
+        self.config.writeable
     }
 
     /// Try to clone the `AliasDb`.
     ///
-    /// A new sqlite connection must be made so this can fail.
+    /// The clone shares the connection pool but has its own pending aliases.
     pub fn try_clone(&self) -> Result<Self, Error> {
         Ok(Self {
             sr_root_dir: self.sr_root_dir.clone(),
             sr_instance: self.sr_instance.clone(),
-            conn: Self::new_connection(&self.sr_root_dir, &self.sr_instance)?,
+            config: self.config.clone(),
+            pool: self.pool.clone(),
+            pending: Default::default(),
         })
     }
 
+    /// Store an alias for the sequence with the given `seq_id`.
+    ///
+    /// The alias is written to the database on the next call to `commit()`.
+    pub fn store_alias(&self, seq_id: &str, alias: NamespacedAlias) -> Result<(), Error> {
+        if !self.config.writeable {
+            return Err(Error::ReadOnly);
+        }
+        self.pending
+            .lock()
+            .map_err(|_| Error::MutexPending)?
+            .push((seq_id.to_string(), alias));
+        Ok(())
+    }
+
+    /// Write all stored aliases to the database and return the number of new aliases.
+    ///
+    /// As in the Python implementation, storing an existing alias for a different
+    /// sequence marks the existing record as not current.
+    pub fn commit(&self) -> Result<usize, Error> {
+        let mut pending = self.pending.lock().map_err(|_| Error::MutexPending)?;
+        let mut locked_conn = self.pool.get()?;
+
+        let tx = locked_conn
+            .transaction()
+            .map_err(|e| Error::AliasDbExec(e.to_string()))?;
+        let mut count = 0;
+        for (seq_id, alias) in pending.iter() {
+            let current = tx
+                .query_row(
+                    "SELECT seqalias_id, seq_id FROM seqalias \
+                    WHERE namespace = ? AND alias = ? AND is_current = 1",
+                    (&alias.namespace.value, &alias.alias),
+                    |row| Ok((row.get::<_, u64>(0)?, row.get::<_, String>(1)?)),
+                )
+                .optional()
+                .map_err(|e| Error::AliasDbExec(e.to_string()))?;
+            match current {
+                Some((_, current_seq_id)) if &current_seq_id == seq_id => continue,
+                Some((seqalias_id, _)) => {
+                    tx.execute(
+                        "UPDATE seqalias SET is_current = 0 WHERE seqalias_id = ?",
+                        [seqalias_id],
+                    )
+                    .map_err(|e| Error::AliasDbExec(e.to_string()))?;
+                }
+                None => (),
+            }
+            tx.execute(
+                "INSERT INTO seqalias (seq_id, namespace, alias) VALUES (?, ?, ?)",
+                (seq_id, &alias.namespace.value, &alias.alias),
+            )
+            .map_err(|e| Error::AliasDbExec(e.to_string()))?;
+            count += 1;
+        }
+        tx.commit().map_err(|e| Error::AliasDbExec(e.to_string()))?;
+        pending.clear();
+
+        Ok(count)
+    }
+
     /// Find aliases an call `f` on each result record.
     ///
     /// The arguments, all optional, restrict the records that are returned, possibly all.
     ///
-    /// Regardless of the query, results are ordered by `seq_id`.
+    /// Results are ordered as given by `query.order_by` and `query.descending`, by
+    /// default by `seq_id`.
     ///
     /// If `query.alias` or `query.seqid` contain `%`, the `like` comparison operator is
     // used.  Otherwise arguments must match exactly.
@@ -145,6 +530,276 @@ impl AliasDb {
         F: FnMut(Result<AliasDbRecord, Error>),
     {
         trace!("AliasDb::find({:?})", &query);
+        let (sql, params) = Self::build_sql(query);
+        trace!("Executing: {:?} with params {:?}", &sql, &params);
+
+        let locked_conn = self.pool.get()?;
+
+        let mut stmt = locked_conn
+            .prepare(&sql)
+            .map_err(|e| Error::AliasDbQuery(format!("{}", e)))?;
+
+        let rows = stmt
+            .query_map(rusqlite::params_from_iter(params), Self::record_from_row)
+            .map_err(|e| Error::AliasDbExec(format!("{}", e)))?;
+        for row in rows {
+            f(row.map_err(|e| Error::AliasDbQuery(format!("Error on row: {}", &e))));
+        }
+
+        Ok(())
+    }
+
+    /// Find aliases and return all result records.
+    ///
+    /// Same as `find()` but fails on the first erroneous record.  The connection is
+    /// only locked while the records are read, so the result can be processed freely.
+    pub fn find_all(&self, query: &Query) -> Result<Vec<AliasDbRecord>, Error> {
+        let mut result = Vec::new();
+        let mut error = None;
+        self.find(query, |record| match record {
+            Ok(record) if error.is_none() => result.push(record),
+            Ok(_) => (),
+            Err(e) => {
+                error.get_or_insert(e);
+            }
+        })?;
+
+        match error {
+            Some(e) => Err(e),
+            None => Ok(result),
+        }
+    }
+
+    /// Find aliases and return the first result record, if any.
+    ///
+    /// The query's `limit` is ignored; `offset` and ordering are respected.
+    pub fn find_one(&self, query: &Query) -> Result<Option<AliasDbRecord>, Error> {
+        let query = Query {
+            limit: Some(1),
+            ..query.clone()
+        };
+        Ok(self.find_all(&query)?.into_iter().next())
+    }
+
+    /// Resolve `alias` in the optional `namespace` to its unique `seq_id`.
+    ///
+    /// Fails if no or more than one sequence has the alias.  See `resolve()` for
+    /// details and for other resolution policies.
+    pub fn resolve_seq_id(&self, alias: &str, namespace: Option<&str>) -> Result<String, Error> {
+        let mut records = self.resolve(alias, namespace, &Default::default())?;
+        Ok(records.remove(0).seqid)
+    }
+
+    /// Resolve `alias` in the optional `namespace` to the matching records.
+    ///
+    /// The namespace may be given as used in identifiers, e.g., `refseq` instead of
+    /// `NCBI`, see `Namespace::normalize()`.  For the `ga4gh` namespace, the alias is
+    /// the `SQ.`-prefixed `seq_id` and for `sha512t24u`, the plain `seq_id`.
+    ///
+    /// If `options.versionless` is set and there is no exact match for an alias
+    /// without version, the highest version of the accession is used.
+    ///
+    /// One record is returned per `seq_id`.  Fails if no sequence has the alias.  If
+    /// several sequences have the alias, `options.policy` decides which records are
+    /// returned; if the alias remains ambiguous, this fails with
+    /// `Error::AliasDbResolutionAmbiguous` unless the policy is `All`.
+    pub fn resolve(
+        &self,
+        alias: &str,
+        namespace: Option<&str>,
+        options: &ResolveOptions,
+    ) -> Result<Vec<AliasDbRecord>, Error> {
+        let namespace = namespace.map(Namespace::normalize);
+        let query = match &namespace {
+            Some(namespace) if namespace.is_seq_id() => Query {
+                seqid: Some(
+                    seq_id_from_alias(namespace, alias)
+                        .ok_or_else(|| Error::AliasDbResolve(alias.to_string()))?
+                        .to_string(),
+                ),
+                ..options.query()
+            },
+            _ => Query {
+                namespace,
+                alias: Some(alias.to_string()),
+                ..options.query()
+            },
+        };
+
+        let mut records = self.find_all(&query)?;
+        if options.as_of.is_some() {
+            records = most_recent_per_alias(records);
+        }
+        let is_versionless = query.seqid.is_none() && split_version(alias).is_none();
+        if records.is_empty() && options.versionless && is_versionless {
+            let base = Query {
+                namespace: query.namespace.clone(),
+                ..options.query()
+            };
+            records = self.latest_versions(alias, &base)?;
+        }
+        if records.is_empty() {
+            return Err(Error::AliasDbResolve(alias.to_string()));
+        }
+
+        let mut records = match &options.policy {
+            ResolutionPolicy::Error | ResolutionPolicy::All => records,
+            ResolutionPolicy::NamespacePriority(namespaces) => namespaces
+                .iter()
+                .map(|namespace| Namespace::normalize(namespace))
+                .find_map(|namespace| {
+                    let matching: Vec<_> = records
+                        .iter()
+                        .filter(|record| record.namespace == namespace)
+                        .cloned()
+                        .collect();
+                    (!matching.is_empty()).then_some(matching)
+                })
+                .unwrap_or(records),
+            ResolutionPolicy::MostRecent => records
+                .into_iter()
+                .max_by_key(|record| (record.added, record.seqalias_id))
+                .into_iter()
+                .collect(),
+        };
+        // Records are ordered by `seq_id`, keep the first one for each.
+        records.dedup_by(|a, b| a.seqid == b.seqid);
+
+        if records.len() > 1 && options.policy != ResolutionPolicy::All {
+            Err(Error::AliasDbResolutionAmbiguous(
+                alias.to_string(),
+                records,
+            ))
+        } else {
+            Ok(records)
+        }
+    }
+
+    /// List all current versions of the unversioned `accession`, e.g., `NM_000551`.
+    ///
+    /// The result is sorted numerically by version, so `.9` comes before `.10`.  The
+    /// namespace may be given as used in identifiers, see `Namespace::normalize()`.
+    pub fn versions(
+        &self,
+        accession: &str,
+        namespace: Option<&str>,
+    ) -> Result<Vec<AliasVersion>, Error> {
+        let base = Query {
+            namespace: namespace.map(Namespace::normalize),
+            ..Default::default()
+        };
+        self.versions_impl(accession, &base)
+    }
+
+    /// Implementation of `versions()` restricting the `base` query.
+    ///
+    /// If `base.added_until` is set, the aliases current at that time are used.
+    fn versions_impl(&self, accession: &str, base: &Query) -> Result<Vec<AliasVersion>, Error> {
+        let query = Query {
+            alias_prefix: Some(format!("{accession}.")),
+            ..base.clone()
+        };
+
+        let mut records = self.find_all(&query)?;
+        if query.added_until.is_some() {
+            records = most_recent_per_alias(records);
+        }
+        let mut result: Vec<_> = records
+            .into_iter()
+            .filter_map(|record| match split_version(&record.alias) {
+                Some((acc, version)) if acc == accession => Some(AliasVersion { version, record }),
+                _ => None,
+            })
+            .collect();
+        result.sort_by(|a, b| {
+            (a.version, &a.record.namespace.value, &a.record.seqid).cmp(&(
+                b.version,
+                &b.record.namespace.value,
+                &b.record.seqid,
+            ))
+        });
+
+        Ok(result)
+    }
+
+    /// Return the records of the highest version of the unversioned `accession`.
+    ///
+    /// Records are ordered by `seq_id`, as expected by `resolve()`.
+    fn latest_versions(&self, accession: &str, base: &Query) -> Result<Vec<AliasDbRecord>, Error> {
+        let versions = self.versions_impl(accession, base)?;
+        let max_version = versions.iter().map(|version| version.version).max();
+        let mut records: Vec<_> = versions
+            .into_iter()
+            .filter(|version| Some(version.version) == max_version)
+            .map(|version| version.record)
+            .collect();
+        records.sort_by(|a, b| a.seqid.cmp(&b.seqid));
+        Ok(records)
+    }
+
+    /// Return all aliases of the sequence that `alias` in `namespace` refers to.
+    ///
+    /// Namespaces are returned as used in identifiers: aliases in the `NCBI` namespace
+    /// are returned in the `refseq` namespace and the `seq_id` itself is returned as
+    /// `SQ.<seq_id>` in the `ga4gh` namespace.  The result is sorted by namespace and
+    /// alias and limited to `target_namespaces`, if given.
+    pub fn translate_alias(
+        &self,
+        alias: &str,
+        namespace: Option<&str>,
+        target_namespaces: Option<&[&str]>,
+    ) -> Result<Vec<NamespacedAlias>, Error> {
+        let seq_id = self.resolve_seq_id(alias, namespace)?;
+        let query = Query {
+            seqid: Some(seq_id.clone()),
+            ..Default::default()
+        };
+
+        let mut result: Vec<_> = self
+            .find_all(&query)?
+            .into_iter()
+            .map(|record| NamespacedAlias {
+                namespace: Namespace::new(record.namespace.canonical_name()),
+                alias: record.alias,
+            })
+            .collect();
+        result.push(NamespacedAlias {
+            namespace: Namespace::new(Namespace::GA4GH),
+            alias: format!("{GA4GH_PREFIX}{seq_id}"),
+        });
+        result.sort_by(|a, b| (&a.namespace.value, &a.alias).cmp(&(&b.namespace.value, &b.alias)));
+        result.dedup();
+
+        if let Some(target_namespaces) = target_namespaces {
+            let target_namespaces: Vec<_> = target_namespaces
+                .iter()
+                .map(|namespace| Namespace::normalize(namespace).canonical_name().to_string())
+                .collect();
+            result.retain(|alias| target_namespaces.contains(&alias.namespace.value));
+        }
+
+        Ok(result)
+    }
+
+    /// Return all identifiers that refer to the same sequence as `identifier`.
+    ///
+    /// Identifiers have the form `namespace:alias`, the namespace is optional on
+    /// input.  See `translate_alias()` for the namespaces and order of the result.
+    pub fn translate_identifier(
+        &self,
+        identifier: &str,
+        target_namespaces: Option<&[&str]>,
+    ) -> Result<Vec<String>, Error> {
+        let (namespace, alias) = split_identifier(identifier);
+        Ok(self
+            .translate_alias(alias, namespace, target_namespaces)?
+            .iter()
+            .map(|alias| alias.to_string())
+            .collect())
+    }
+
+    /// Build SQL query and parameters for the given `query`.
+    fn build_sql(query: &Query) -> (String, Vec<Value>) {
         fn eq_or_like(s: &str) -> &'static str {
             if s.contains('%') {
                 "like"
@@ -154,7 +809,7 @@ impl AliasDb {
         }
 
         let mut clauses = Vec::new();
-        let mut params: Vec<rusqlite::types::Value> = Vec::new();
+        let mut params: Vec<Value> = Vec::new();
 
         // Add namespace to query if provided.
         if let Some(namespace) = &query.namespace {
@@ -167,6 +822,25 @@ impl AliasDb {
             clauses.push(format!("alias {} ?", eq_or_like(alias)));
             params.push(Value::Text(alias.to_string()));
         }
+        // Add alias prefix as range to query if provided.
+        if let Some(prefix) = query.alias_prefix.as_deref() {
+            let mut chars: Vec<_> = prefix.chars().collect();
+            let upper = chars
+                .pop()
+                .and_then(|c| char::from_u32(c as u32 + 1))
+                .map(|c| {
+                    chars
+                        .into_iter()
+                        .chain(std::iter::once(c))
+                        .collect::<String>()
+                });
+            clauses.push("alias >= ?".to_string());
+            params.push(Value::Text(prefix.to_string()));
+            if let Some(upper) = upper {
+                clauses.push("alias < ?".to_string());
+                params.push(Value::Text(upper));
+            }
+        }
         // Add seqid to query if provided.
         if let Some(seqid) = query.seqid.as_deref() {
             clauses.push(format!("seq_id {} ?", eq_or_like(seqid)));
@@ -176,6 +850,13 @@ impl AliasDb {
         if query.current_only {
             clauses.push("is_current = 1".to_string());
         }
+        // Possibly limit to the ones added until the given time.
+        if let Some(added_until) = query.added_until {
+            clauses.push("added <= ?".to_string());
+            params.push(Value::Text(
+                added_until.format("%Y-%m-%d %H:%M:%S").to_string(),
+            ));
+        }
 
         // Prepare SQL query.
         let cols = &[
@@ -192,35 +873,45 @@ impl AliasDb {
             let clauses: Vec<_> = clauses.iter().map(|s| format!("({s})")).collect();
             sql.push_str(&clauses.join(" AND "));
         }
-        sql.push_str(" ORDER BY seq_id, namespace, alias");
-        trace!("Executing: {:?} with params {:?}", &sql, &params);
-
-        let locked_conn = self.conn.lock().map_err(|_| Error::MutexSqlite)?;
 
-        let mut stmt = locked_conn
-            .prepare(&sql)
-            .map_err(|e| Error::AliasDbQuery(format!("{}", e)))?;
+        // Add ordering, the direction applies to all columns.
+        let direction = if query.descending { "DESC" } else { "ASC" };
+        let order_by: Vec<_> = query
+            .order_by
+            .columns()
+            .iter()
+            .map(|col| format!("{col} {direction}"))
+            .collect();
+        sql.push_str(&format!(" ORDER BY {}", order_by.join(", ")));
 
-        let rows = stmt
-            .query_map(rusqlite::params_from_iter(params), |row| {
-                let added: String = row.get(3)?;
-                let added = NaiveDateTime::parse_from_str(&added, "%Y-%m-%d %H:%M:%S")
-                    .expect("could not convert timestamp");
-                Ok(AliasDbRecord {
-                    seqalias_id: row.get(0)?,
-                    seqid: row.get(1)?,
-                    alias: row.get(2)?,
-                    added,
-                    is_current: row.get(4)?,
-                    namespace: Namespace::from(row.get(5)?),
-                })
-            })
-            .map_err(|e| Error::AliasDbExec(format!("{}", e)))?;
-        for row in rows {
-            f(row.map_err(|e| Error::AliasDbQuery(format!("Error on row: {}", &e))));
+        // Add pagination, SQLite requires a limit when an offset is given.
+        if query.limit.is_some() || query.offset.is_some() {
+            let limit = query.limit.map(|limit| limit as i64).unwrap_or(-1);
+            sql.push_str(" LIMIT ?");
+            params.push(Value::Integer(limit));
+            if let Some(offset) = query.offset {
+                sql.push_str(" OFFSET ?");
+                params.push(Value::Integer(offset as i64));
+            }
         }
 
-        Ok(())
+        (sql, params)
+    }
+
+    /// Convert result `row` into an `AliasDbRecord`.
+    fn record_from_row(row: &rusqlite::Row) -> Result<AliasDbRecord, rusqlite::Error> {
+        let added: String = row.get(3)?;
+        let added = NaiveDateTime::parse_from_str(&added, "%Y-%m-%d %H:%M:%S").map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(3, rusqlite::types::Type::Text, Box::new(e))
+        })?;
+        Ok(AliasDbRecord {
+            seqalias_id: row.get(0)?,
+            seqid: row.get(1)?,
+            alias: row.get(2)?,
+            added,
+            is_current: row.get(4)?,
+            namespace: Namespace::from(row.get(5)?),
+        })
     }
 }
 
@@ -231,7 +922,12 @@ mod test {
 
     use pretty_assertions::assert_eq;
 
-    use super::{AliasDb, Namespace, Query};
+    use chrono::NaiveDateTime;
+
+    use super::{
+        AliasDb, AliasDbConfig, Namespace, NamespacedAlias, OrderBy, Query, ResolutionPolicy,
+        ResolveOptions,
+    };
 
     #[test]
     fn test_sync() {
@@ -273,6 +969,27 @@ mod test {
         run(&second)
     }
 
+    #[test]
+    fn find_concurrent() -> Result<(), Error> {
+        let aliases = AliasDb::new_with_config(
+            &PathBuf::from("tests/data"),
+            "aliases",
+            AliasDbConfig {
+                connection_pool_size: 2,
+                ..Default::default()
+            },
+        )?;
+
+        std::thread::scope(|s| {
+            let handles = (0..4)
+                .map(|_| s.spawn(|| run(&aliases)))
+                .collect::<Vec<_>>();
+            handles
+                .into_iter()
+                .try_for_each(|handle| handle.join().unwrap())
+        })
+    }
+
     #[test]
     fn find_wildcard() -> Result<(), Error> {
         let aliases = AliasDb::new(&PathBuf::from("tests/data"), "aliases")?;
@@ -327,6 +1044,245 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    fn find_all_ordered() -> Result<(), Error> {
+        let aliases = AliasDb::new(&PathBuf::from("tests/data"), "aliases")?;
+
+        let values: Vec<_> = aliases
+            .find_all(&Query {
+                order_by: OrderBy::Alias,
+                descending: true,
+                ..Default::default()
+            })?
+            .into_iter()
+            .map(|record| record.namespace.value)
+            .collect();
+
+        assert_eq!(values, vec!["VMC", "SHA1", "SEGUID", "NCBI", "MD5"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn find_all_paginated() -> Result<(), Error> {
+        let aliases = AliasDb::new(&PathBuf::from("tests/data"), "aliases")?;
+
+        let page = |limit, offset| -> Result<Vec<String>, Error> {
+            Ok(aliases
+                .find_all(&Query {
+                    order_by: OrderBy::Alias,
+                    limit,
+                    offset,
+                    ..Default::default()
+                })?
+                .into_iter()
+                .map(|record| record.namespace.value)
+                .collect())
+        };
+
+        assert_eq!(page(Some(2), None)?, vec!["MD5", "NCBI"]);
+        assert_eq!(page(Some(2), Some(2))?, vec!["SEGUID", "SHA1"]);
+        assert_eq!(page(None, Some(4))?, vec!["VMC"]);
+        assert!(page(Some(2), Some(5))?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn find_one() -> Result<(), Error> {
+        let aliases = AliasDb::new(&PathBuf::from("tests/data"), "aliases")?;
+
+        let record = aliases.find_one(&Query {
+            alias: Some("NM_001304430.2".to_string()),
+            ..Default::default()
+        })?;
+        assert_eq!(
+            record.map(|record| record.seqid),
+            Some("5q5HZTCRudL17NTiv5Bn6th__0FrZH04".to_string())
+        );
+
+        let record = aliases.find_one(&Query {
+            alias: Some("NM_000000.0".to_string()),
+            ..Default::default()
+        })?;
+        assert!(record.is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn namespace_normalize() {
+        for (value, expected, canonical) in [
+            ("refseq", "NCBI", "refseq"),
+            ("RefSeq", "NCBI", "refseq"),
+            ("NCBI", "NCBI", "refseq"),
+            ("ensembl", "Ensembl", "Ensembl"),
+            ("md5", "MD5", "MD5"),
+            ("vmc", "VMC", "VMC"),
+            ("GRCh38", "GRCh38", "GRCh38"),
+        ] {
+            let namespace = Namespace::normalize(value);
+            assert_eq!(namespace.value, expected);
+            assert_eq!(namespace.canonical_name(), canonical);
+        }
+    }
+
+    #[test]
+    fn versions() -> Result<(), Error> {
+        let aliases = AliasDb::new(&PathBuf::from("tests/data"), "aliases")?;
+
+        let versions = aliases.versions("NM_001304430", Some("refseq"))?;
+        assert_eq!(versions.len(), 1);
+        assert_eq!(versions[0].version, 2);
+        assert_eq!(versions[0].record.alias, "NM_001304430.2");
+        assert!(aliases.versions("NM_00130443", None)?.is_empty());
+
+        assert!(aliases.resolve_seq_id("NM_001304430", None).is_err());
+        let records = aliases.resolve(
+            "NM_001304430",
+            None,
+            &ResolveOptions {
+                versionless: true,
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].alias, "NM_001304430.2");
+
+        Ok(())
+    }
+
+    #[test]
+    fn resolve_historical() -> Result<(), Error> {
+        let temp = temp_testdir::TempDir::default();
+        std::fs::create_dir_all(temp.join("latest"))?;
+        let aliases = AliasDb::init(&temp.as_ref(), "latest")?;
+
+        let x = NamespacedAlias {
+            namespace: Namespace::new("NCBI"),
+            alias: "X".to_string(),
+        };
+        aliases.store_alias("old", x.clone())?;
+        aliases.commit()?;
+        aliases.store_alias("new", x)?;
+        aliases.commit()?;
+        {
+            let conn = aliases.pool.get()?;
+            conn.execute(
+                "UPDATE seqalias SET added = '2020-01-01 00:00:00' WHERE seq_id = 'old'",
+                [],
+            )?;
+            conn.execute(
+                "UPDATE seqalias SET added = '2022-01-01 00:00:00' WHERE seq_id = 'new'",
+                [],
+            )?;
+        }
+
+        let resolve = |options: ResolveOptions| -> Result<Vec<String>, crate::Error> {
+            Ok(aliases
+                .resolve("X", Some("refseq"), &options)?
+                .into_iter()
+                .map(|record| record.seqid)
+                .collect())
+        };
+        let as_of = |date: &str| ResolveOptions {
+            as_of: Some(NaiveDateTime::parse_from_str(date, "%Y-%m-%d %H:%M:%S").unwrap()),
+            ..Default::default()
+        };
+
+        assert_eq!(resolve(Default::default())?, vec!["new"]);
+        assert_eq!(resolve(as_of("2021-01-01 00:00:00"))?, vec!["old"]);
+        assert_eq!(resolve(as_of("2022-01-01 00:00:00"))?, vec!["new"]);
+        assert!(resolve(as_of("2019-01-01 00:00:00")).is_err());
+
+        let include_retired = ResolveOptions {
+            include_retired: true,
+            ..Default::default()
+        };
+        assert!(resolve(include_retired.clone()).is_err());
+        assert_eq!(
+            resolve(ResolveOptions {
+                policy: ResolutionPolicy::All,
+                ..include_retired.clone()
+            })?,
+            vec!["new", "old"]
+        );
+        assert_eq!(
+            resolve(ResolveOptions {
+                policy: ResolutionPolicy::MostRecent,
+                ..include_retired
+            })?,
+            vec!["new"]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn split_version() {
+        assert_eq!(
+            super::split_version("NM_000551.10"),
+            Some(("NM_000551", 10))
+        );
+        assert_eq!(super::split_version("NM_000551"), None);
+        assert_eq!(super::split_version("NM_000551."), None);
+        assert_eq!(super::split_version("SQ.abc"), None);
+    }
+
+    #[test]
+    fn translate_identifier() -> Result<(), Error> {
+        let aliases = AliasDb::new(&PathBuf::from("tests/data"), "aliases")?;
+        let all = vec![
+            "MD5:a8e7e4cbd2fa521b45b23692b2dd601c",
+            "SEGUID:U5AvKXlRSRwJgn/Zxsa286iO/sg",
+            "SHA1:53902f297951491c09827fd9c6c6b6f3a88efec8",
+            "VMC:GS_5q5HZTCRudL17NTiv5Bn6th__0FrZH04",
+            "ga4gh:SQ.5q5HZTCRudL17NTiv5Bn6th__0FrZH04",
+            "refseq:NM_001304430.2",
+        ];
+
+        assert_eq!(aliases.translate_identifier("NM_001304430.2", None)?, all);
+        assert_eq!(
+            aliases.translate_identifier("refseq:NM_001304430.2", None)?,
+            all
+        );
+        assert_eq!(
+            aliases.translate_identifier("ga4gh:SQ.5q5HZTCRudL17NTiv5Bn6th__0FrZH04", None)?,
+            all
+        );
+        assert_eq!(
+            aliases.translate_identifier(
+                "MD5:a8e7e4cbd2fa521b45b23692b2dd601c",
+                Some(&["refseq", "ga4gh"])
+            )?,
+            vec![
+                "ga4gh:SQ.5q5HZTCRudL17NTiv5Bn6th__0FrZH04",
+                "refseq:NM_001304430.2",
+            ]
+        );
+        assert!(aliases
+            .translate_identifier("refseq:NM_000000.0", None)
+            .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn translate_alias() -> Result<(), Error> {
+        let aliases = AliasDb::new(&PathBuf::from("tests/data"), "aliases")?;
+
+        let result = aliases.translate_alias("NM_001304430.2", Some("NCBI"), Some(&["MD5"]))?;
+        assert_eq!(
+            result,
+            vec![NamespacedAlias {
+                namespace: Namespace::new("MD5"),
+                alias: "a8e7e4cbd2fa521b45b23692b2dd601c".to_string(),
+            }]
+        );
+
+        Ok(())
+    }
 }
 
 // <LICENSE>
@@ -0,0 +1,249 @@
+//! Asynchronous interface for use with `tokio`.
+//!
+//! The `SeqRepo` implementation performs blocking SQLite and file I/O.  The
+//! `AsyncSeqRepo` wrapper runs such calls on `tokio`'s blocking thread pool via
+//! `spawn_blocking()`, so they do not stall the async runtime.
+
+use std::future::Future;
+use std::ops::Range;
+use std::sync::Arc;
+
+use crate::error::Error;
+use crate::interface::{AliasOrSeqId, Interface, SequenceInfo};
+use crate::repo::SeqRepo;
+
+/// Asynchronous variant of the `Interface` trait.
+pub trait AsyncInterface: Send + Sync {
+    /// Fetch sequence given an alias.
+    fn fetch_sequence(
+        &self,
+        alias_or_seq_id: &AliasOrSeqId,
+    ) -> impl Future<Output = Result<String, Error>> + Send {
+        self.fetch_sequence_part(alias_or_seq_id, None, None)
+    }
+
+    /// Fetch part sequence given an alias.
+    fn fetch_sequence_part(
+        &self,
+        alias_or_seq_id: &AliasOrSeqId,
+        begin: Option<usize>,
+        end: Option<usize>,
+    ) -> impl Future<Output = Result<String, Error>> + Send;
+
+    /// Fetch many sequence parts given as alias and range at once.
+    ///
+    /// Results are returned in input order, see `Interface::fetch_sequence_parts()`.
+    /// The default implementation awaits `fetch_sequence_part()` for each request.
+    fn fetch_sequence_parts(
+        &self,
+        requests: &[(AliasOrSeqId, Range<usize>)],
+    ) -> impl Future<Output = Result<Vec<String>, Error>> + Send {
+        async move {
+            let mut result = Vec::with_capacity(requests.len());
+            for (alias_or_seq_id, range) in requests {
+                result.push(
+                    self.fetch_sequence_part(alias_or_seq_id, Some(range.start), Some(range.end))
+                        .await?,
+                );
+            }
+            Ok(result)
+        }
+    }
+
+    /// Fetch the metadata of the sequence given an alias.
+    ///
+    /// See `Interface::sequence_info()` for the default implementation.
+    fn sequence_info(
+        &self,
+        alias_or_seq_id: &AliasOrSeqId,
+    ) -> impl Future<Output = Result<SequenceInfo, Error>> + Send {
+        let seq = self.fetch_sequence(alias_or_seq_id);
+        async move { SequenceInfo::from_sequence(&seq.await?) }
+    }
+}
+
+/// Asynchronous wrapper around a blocking `Interface` implementation.
+///
+/// Each call is run with `tokio::task::spawn_blocking()` and thus must be made from
+/// within a `tokio` runtime.  Wrap other implementations such as `CacheReadingSeqRepo`
+/// as well, so that their `Interface` methods stay unambiguous.
+#[derive(Debug)]
+pub struct AsyncSeqRepo<T = SeqRepo> {
+    /// The wrapped blocking implementation.
+    inner: Arc<T>,
+}
+
+impl<T> Clone for AsyncSeqRepo<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T> AsyncSeqRepo<T>
+where
+    T: Interface + Send + Sync + 'static,
+{
+    /// Wrap the blocking implementation `inner`.
+    pub fn new(inner: T) -> Self {
+        Self::from_arc(Arc::new(inner))
+    }
+
+    /// Wrap the shared blocking implementation `inner`.
+    pub fn from_arc(inner: Arc<T>) -> Self {
+        Self { inner }
+    }
+
+    /// Provide access to the wrapped implementation, e.g., for blocking calls.
+    pub fn inner(&self) -> &Arc<T> {
+        &self.inner
+    }
+
+    /// Run `f` on the wrapped implementation on the blocking thread pool.
+    async fn spawn<F, R>(&self, f: F) -> Result<R, Error>
+    where
+        F: FnOnce(&T) -> Result<R, Error> + Send + 'static,
+        R: Send + 'static,
+    {
+        let inner = self.inner.clone();
+        tokio::task::spawn_blocking(move || f(&inner))
+            .await
+            .map_err(|e| Error::AsyncJoin(e.to_string()))?
+    }
+}
+
+impl<T> AsyncInterface for AsyncSeqRepo<T>
+where
+    T: Interface + Send + Sync + 'static,
+{
+    fn fetch_sequence_part(
+        &self,
+        alias_or_seq_id: &AliasOrSeqId,
+        begin: Option<usize>,
+        end: Option<usize>,
+    ) -> impl Future<Output = Result<String, Error>> + Send {
+        let alias_or_seq_id = alias_or_seq_id.clone();
+        self.spawn(move |inner| inner.fetch_sequence_part(&alias_or_seq_id, begin, end))
+    }
+
+    fn fetch_sequence_parts(
+        &self,
+        requests: &[(AliasOrSeqId, Range<usize>)],
+    ) -> impl Future<Output = Result<Vec<String>, Error>> + Send {
+        let requests = requests.to_vec();
+        self.spawn(move |inner| inner.fetch_sequence_parts(&requests))
+    }
+
+    fn sequence_info(
+        &self,
+        alias_or_seq_id: &AliasOrSeqId,
+    ) -> impl Future<Output = Result<SequenceInfo, Error>> + Send {
+        let alias_or_seq_id = alias_or_seq_id.clone();
+        self.spawn(move |inner| inner.sequence_info(&alias_or_seq_id))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::future::Future;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::{AliasOrSeqId, AsyncInterface, AsyncSeqRepo, Error, SeqRepo};
+
+    async fn test_fetch(sr: &impl AsyncInterface) -> Result<(), anyhow::Error> {
+        let aos: AliasOrSeqId = "refseq:NM_001304430.2".parse()?;
+
+        assert_eq!(sr.fetch_sequence(&aos).await?.len(), 1873);
+        assert_eq!(
+            sr.fetch_sequence_part(&aos, Some(0), Some(10)).await?,
+            "ACTGCTGAGC"
+        );
+        assert_eq!(
+            sr.fetch_sequence_parts(&[(aos.clone(), 100..110), (aos.clone(), 0..4)])
+                .await?,
+            vec!["ATGTAGGTAA", "ACTG"]
+        );
+        assert_eq!(sr.sequence_info(&aos).await?.len, 1873);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn async_seq_repo() -> Result<(), anyhow::Error> {
+        let sr = AsyncSeqRepo::new(SeqRepo::new("tests/data/seqrepo", "latest")?);
+        test_fetch(&sr).await?;
+
+        // Clones share the wrapped implementation and can be moved into tasks.
+        let cloned = sr.clone();
+        tokio::spawn(async move { test_fetch(&cloned).await.unwrap() }).await?;
+
+        Ok(())
+    }
+
+    /// Implementation providing only the required methods of `AsyncInterface`.
+    struct FetchOnly(AsyncSeqRepo);
+
+    impl AsyncInterface for FetchOnly {
+        fn fetch_sequence_part(
+            &self,
+            alias_or_seq_id: &AliasOrSeqId,
+            begin: Option<usize>,
+            end: Option<usize>,
+        ) -> impl Future<Output = Result<String, Error>> + Send {
+            self.0.fetch_sequence_part(alias_or_seq_id, begin, end)
+        }
+    }
+
+    #[tokio::test]
+    async fn sequence_info_default() -> Result<(), anyhow::Error> {
+        let sr = FetchOnly(AsyncSeqRepo::new(SeqRepo::new(
+            "tests/data/seqrepo",
+            "latest",
+        )?));
+        test_fetch(&sr).await?;
+        let aos: AliasOrSeqId = "refseq:NM_001304430.2".parse()?;
+        assert_eq!(
+            sr.sequence_info(&aos).await?.seq_id,
+            "5q5HZTCRudL17NTiv5Bn6th__0FrZH04"
+        );
+
+        Ok(())
+    }
+
+    #[cfg(feature = "cached")]
+    #[tokio::test]
+    async fn cache_reading() -> Result<(), anyhow::Error> {
+        use crate::Interface;
+
+        let cr = crate::CacheReadingSeqRepo::new("tests/data/cached/cache.fasta")?;
+        let aos: AliasOrSeqId = "NM_001304430.2".parse()?;
+        // Both traits are in scope, the blocking methods must not be ambiguous.
+        assert_eq!(cr.fetch_sequence_part(&aos, Some(0), Some(4))?, "ACTG");
+        let cr = AsyncSeqRepo::new(cr);
+        assert_eq!(
+            cr.fetch_sequence_part(&aos, Some(0), Some(4)).await?,
+            "ACTG"
+        );
+
+        Ok(())
+    }
+}
+
+// <LICENSE>
+// Copyright 2023 seqrepo-rs Contributors
+// Copyright 2016 biocommons.seqrepo Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+// </LICENSE>
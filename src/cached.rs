@@ -12,24 +12,32 @@ use std::{
     sync::{Arc, Mutex},
 };
 
+use chrono::NaiveDateTime;
+
 use crate::repo::SeqRepo;
 use crate::{
     error::Error,
-    interface::{AliasOrSeqId, Interface},
+    interface::{AliasOrSeqId, Interface, SequenceInfo},
+    Namespace, NamespacedAlias, Strand,
 };
 
+/// Format of the `added` timestamp in cached sequence info.
+static ADDED_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+
 /// Sequence repository reading from actual implementation and writing to a cache.
-pub struct CacheWritingSeqRepo {
+///
+/// Any `Interface` implementation can be recorded from, e.g., a `RefgetSeqRepo`.
+pub struct CacheWritingSeqRepo<T = SeqRepo> {
     /// Path to the cache file to write to.
     writer: Arc<Mutex<noodles::fasta::Writer<BufWriter<File>>>>,
     /// The actual implementation used for reading.
-    repo: SeqRepo,
+    repo: T,
     /// The internal cache built when writing.
     cache: Arc<Mutex<HashMap<String, String>>>,
 }
 
-impl CacheWritingSeqRepo {
-    pub fn new<P>(repo: SeqRepo, cache_path: P) -> Result<Self, Error>
+impl<T> CacheWritingSeqRepo<T> {
+    pub fn new<P>(repo: T, cache_path: P) -> Result<Self, Error>
     where
         P: AsRef<Path>,
     {
@@ -53,9 +61,29 @@ impl CacheWritingSeqRepo {
             cache,
         })
     }
+
+    /// Store `value` under `key` in the internal cache and the cache file.
+    fn store(&self, key: String, value: &str) -> Result<(), Error> {
+        self.cache
+            .as_ref()
+            .lock()
+            .expect("could not acquire lock")
+            .insert(key.clone(), value.to_string());
+        self.writer
+            .lock()
+            .expect("could not acquire lock")
+            .write_record(&noodles::fasta::Record::new(
+                noodles::fasta::record::Definition::new(key, None),
+                noodles::fasta::record::Sequence::from(value.as_bytes().to_vec()),
+            ))
+            .map_err(|e| Error::SeqSepoCacheWrite(e.to_string()))
+    }
 }
 
-impl Interface for CacheWritingSeqRepo {
+impl<T> Interface for CacheWritingSeqRepo<T>
+where
+    T: Interface,
+{
     fn fetch_sequence_part(
         &self,
         alias_or_seq_id: &AliasOrSeqId,
@@ -74,20 +102,54 @@ impl Interface for CacheWritingSeqRepo {
         }
 
         let value = self.repo.fetch_sequence_part(alias_or_seq_id, begin, end)?;
-        self.cache
+        self.store(key, &value)?;
+        Ok(value)
+    }
+
+    fn fetch_sequence_part_stranded(
+        &self,
+        alias_or_seq_id: &AliasOrSeqId,
+        begin: Option<usize>,
+        end: Option<usize>,
+        strand: Strand,
+    ) -> Result<String, Error> {
+        if strand == Strand::Forward {
+            return self.fetch_sequence_part(alias_or_seq_id, begin, end);
+        }
+
+        let key = build_stranded_key(alias_or_seq_id, begin, end, strand);
+        if let Some(value) = self
+            .cache
             .as_ref()
             .lock()
             .expect("could not acquire lock")
-            .insert(key.clone(), value.clone());
-        self.writer
+            .get(&key)
+        {
+            return Ok(value.to_owned());
+        }
+
+        let value = self
+            .repo
+            .fetch_sequence_part_stranded(alias_or_seq_id, begin, end, strand)?;
+        self.store(key, &value)?;
+        Ok(value)
+    }
+
+    fn sequence_info(&self, alias_or_seq_id: &AliasOrSeqId) -> Result<SequenceInfo, Error> {
+        let key = build_info_key(alias_or_seq_id);
+        if let Some(value) = self
+            .cache
+            .as_ref()
             .lock()
             .expect("could not acquire lock")
-            .write_record(&noodles::fasta::Record::new(
-                noodles::fasta::record::Definition::new(key, None),
-                noodles::fasta::record::Sequence::from(value.as_bytes().to_vec()),
-            ))
-            .map_err(|e| Error::SeqSepoCacheWrite(e.to_string()))?;
-        Ok(value)
+            .get(&key)
+        {
+            return decode_info(&key, value);
+        }
+
+        let info = self.repo.sequence_info(alias_or_seq_id)?;
+        self.store(key, &encode_info(&info))?;
+        Ok(info)
     }
 }
 
@@ -143,6 +205,123 @@ impl Interface for CacheReadingSeqRepo {
             Err(Error::SeqSepoCacheKey(key))
         }
     }
+
+    fn fetch_sequence_part_into(
+        &self,
+        alias_or_seq_id: &AliasOrSeqId,
+        begin: Option<usize>,
+        end: Option<usize>,
+        buf: &mut Vec<u8>,
+    ) -> Result<(), Error> {
+        let key = build_key(alias_or_seq_id, begin, end);
+        if let Some(seq) = self.cache.get(&key) {
+            buf.extend_from_slice(seq.as_bytes());
+            Ok(())
+        } else {
+            Err(Error::SeqSepoCacheKey(key))
+        }
+    }
+
+    fn fetch_sequence_part_stranded(
+        &self,
+        alias_or_seq_id: &AliasOrSeqId,
+        begin: Option<usize>,
+        end: Option<usize>,
+        strand: Strand,
+    ) -> Result<String, Error> {
+        let key = build_stranded_key(alias_or_seq_id, begin, end, strand);
+        if let Some(seq) = self.cache.get(&key) {
+            Ok(seq.clone())
+        } else {
+            Err(Error::SeqSepoCacheKey(key))
+        }
+    }
+
+    fn sequence_info(&self, alias_or_seq_id: &AliasOrSeqId) -> Result<SequenceInfo, Error> {
+        let key = build_info_key(alias_or_seq_id);
+        if let Some(value) = self.cache.get(&key) {
+            decode_info(&key, value)
+        } else {
+            Err(Error::SeqSepoCacheKey(key))
+        }
+    }
+}
+
+/// Key of the sequence part on `strand`, the reverse strand has the suffix `:-`.
+fn build_stranded_key(
+    alias_or_seq_id: &AliasOrSeqId,
+    begin: Option<usize>,
+    end: Option<usize>,
+    strand: Strand,
+) -> String {
+    let key = build_key(alias_or_seq_id, begin, end);
+    match strand {
+        Strand::Forward => key,
+        Strand::Reverse => format!("{key}:{strand}"),
+    }
+}
+
+/// Key of the sequence info, the key of the whole sequence with suffix `:info`.
+fn build_info_key(alias_or_seq_id: &AliasOrSeqId) -> String {
+    format!("{}:info", build_key(alias_or_seq_id, None, None))
+}
+
+/// Encode `info` for the cache file.
+///
+/// The fields are separated by `;` and the aliases by `,` so that the value does
+/// not contain whitespace and survives line wrapping in the FASTA file.
+fn encode_info(info: &SequenceInfo) -> String {
+    let aliases = info
+        .aliases
+        .iter()
+        .map(|alias| alias.to_string())
+        .collect::<Vec<_>>()
+        .join(",");
+    format!(
+        "{};{};{};{};{}",
+        info.seq_id,
+        info.len,
+        info.alphabet.as_deref().unwrap_or_default(),
+        info.added
+            .map(|added| added.format(ADDED_FORMAT).to_string())
+            .unwrap_or_default(),
+        aliases
+    )
+}
+
+/// Decode sequence info stored under `key` by `encode_info()`.
+fn decode_info(key: &str, value: &str) -> Result<SequenceInfo, Error> {
+    let invalid = || Error::SeqSepoCacheRead(format!("invalid sequence info for {key}: {value}"));
+
+    let fields = value.split(';').collect::<Vec<_>>();
+    let [seq_id, len, alphabet, added, aliases] = fields[..] else {
+        return Err(invalid());
+    };
+    let aliases = aliases
+        .split(',')
+        .filter(|alias| !alias.is_empty())
+        .map(|alias| {
+            alias
+                .split_once(':')
+                .map(|(namespace, alias)| NamespacedAlias {
+                    namespace: Namespace::new(namespace),
+                    alias: alias.to_string(),
+                })
+                .ok_or_else(invalid)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(SequenceInfo {
+        seq_id: seq_id.to_string(),
+        len: len.parse().map_err(|_| invalid())?,
+        alphabet: Some(alphabet.to_string()).filter(|alphabet| !alphabet.is_empty()),
+        added: Some(added)
+            .filter(|added| !added.is_empty())
+            .map(|added| NaiveDateTime::parse_from_str(added, ADDED_FORMAT))
+            .transpose()
+            .map_err(|_| invalid())?,
+        aliases,
+    })
 }
 
 fn build_key(alias_or_seq_id: &AliasOrSeqId, begin: Option<usize>, end: Option<usize>) -> String {
@@ -184,7 +363,7 @@ mod test {
     use pretty_assertions::assert_eq;
     use temp_testdir::TempDir;
 
-    use crate::{AliasOrSeqId, CacheReadingSeqRepo, Interface, SeqRepo};
+    use crate::{AliasOrSeqId, CacheReadingSeqRepo, Interface, SeqRepo, Strand};
 
     use super::CacheWritingSeqRepo;
 
@@ -237,6 +416,25 @@ mod test {
         assert_eq!(sr.fetch_sequence_part(&aos, None, Some(4))?, "ACTG");
         assert_eq!(sr.fetch_sequence_part(&aos, Some(1869), None)?, "TATA");
         assert_eq!(sr.fetch_sequence_part(&aos, Some(0), Some(4))?, "ACTG");
+        assert_eq!(
+            sr.fetch_sequence_part_stranded(&aos, Some(0), Some(4), Strand::Forward)?,
+            "ACTG"
+        );
+        assert_eq!(
+            sr.fetch_sequence_part_stranded(&aos, Some(0), Some(4), Strand::Reverse)?,
+            "CAGT"
+        );
+
+        let info = sr.sequence_info(&aos)?;
+        assert_eq!(info.seq_id, "5q5HZTCRudL17NTiv5Bn6th__0FrZH04");
+        assert_eq!(info.len, 1873);
+        assert_eq!(info.alphabet.as_deref(), Some("ACGT"));
+        assert_eq!(
+            info.added.map(|added| added.to_string()).as_deref(),
+            Some("2023-02-16 09:46:06")
+        );
+        assert_eq!(info.aliases.len(), 5);
+        assert_eq!(info.aliases[1].to_string(), "NCBI:NM_001304430.2");
 
         Ok(())
     }
@@ -0,0 +1,221 @@
+//! Computation of sequence digests as used for the aliases of sequences.
+//!
+//! The `seq_*` functions normalize the sequence in the same way as the Python
+//! implementation (`bioutils.digests`) before computing the digest.
+
+use base64::engine::general_purpose::{STANDARD_NO_PAD, URL_SAFE};
+use base64::Engine;
+use md5::Md5;
+use sha1::Sha1;
+use sha2::{Digest, Sha512};
+
+use crate::error::Error;
+
+/// Normalize `seq` for computing digests.
+///
+/// Whitespace and asterisks are removed and the sequence is upper-cased.  Fails if
+/// the result contains characters other than `A-Z`.
+pub fn normalize_sequence(seq: &str) -> Result<String, Error> {
+    let normalized: String = seq
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '*')
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+    if let Some(pos) = normalized.find(|c: char| !c.is_ascii_uppercase()) {
+        return Err(Error::InvalidSequence(format!(
+            "normalized sequence contains non-alphabetic character at position {pos}"
+        )));
+    }
+    Ok(normalized)
+}
+
+/// Compute the `sha512t24u` digest of `seq`, used as the `seq_id` in the repository.
+pub fn seq_sha512t24u(seq: &str) -> Result<String, Error> {
+    Ok(sha512t24u(normalize_sequence(seq)?.as_bytes()))
+}
+
+/// Compute the GA4GH identifier (`ga4gh:SQ.<sha512t24u>`) of `seq`.
+pub fn seq_ga4gh_identifier(seq: &str) -> Result<String, Error> {
+    Ok(ga4gh_identifier(&seq_sha512t24u(seq)?))
+}
+
+/// Compute the hex-encoded MD5 digest of `seq`.
+pub fn seq_md5(seq: &str) -> Result<String, Error> {
+    Ok(md5(normalize_sequence(seq)?.as_bytes()))
+}
+
+/// Compute the SEGUID of `seq`.
+pub fn seq_seguid(seq: &str) -> Result<String, Error> {
+    Ok(seguid(normalize_sequence(seq)?.as_bytes()))
+}
+
+/// Compute the hex-encoded SHA-1 digest of `seq`.
+pub fn seq_sha1(seq: &str) -> Result<String, Error> {
+    Ok(sha1(normalize_sequence(seq)?.as_bytes()))
+}
+
+/// Build the GA4GH identifier from a `sha512t24u` digest.
+pub fn ga4gh_identifier(sha512t24u: &str) -> String {
+    format!("ga4gh:SQ.{sha512t24u}")
+}
+
+/// All digests of a sequence that are registered as aliases in the repository.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SequenceDigests {
+    /// The `sha512t24u` digest, i.e., the `seq_id`.
+    pub sha512t24u: String,
+    /// The hex-encoded MD5 digest.
+    pub md5: String,
+    /// The SEGUID.
+    pub seguid: String,
+    /// The hex-encoded SHA-1 digest.
+    pub sha1: String,
+}
+
+impl SequenceDigests {
+    /// Compute all digests of `seq`, normalizing it only once.
+    pub fn new(seq: &str) -> Result<Self, Error> {
+        Ok(Self::from_normalized(&normalize_sequence(seq)?))
+    }
+
+    /// Compute all digests of the already normalized sequence `seq`.
+    pub(crate) fn from_normalized(seq: &str) -> Self {
+        let seq = seq.as_bytes();
+        Self {
+            sha512t24u: sha512t24u(seq),
+            md5: md5(seq),
+            seguid: seguid(seq),
+            sha1: sha1(seq),
+        }
+    }
+
+    /// The GA4GH identifier (`ga4gh:SQ.<sha512t24u>`).
+    pub fn ga4gh_identifier(&self) -> String {
+        ga4gh_identifier(&self.sha512t24u)
+    }
+
+    /// The alias in the `VMC` namespace (`GS_<sha512t24u>`).
+    pub fn vmc_alias(&self) -> String {
+        format!("GS_{}", self.sha512t24u)
+    }
+}
+
+/// Compute the truncated SHA-512 digest (`sha512t24u`) of `seq`.
+///
+/// This is the URL-safe base64 encoding of the first 24 bytes of the SHA-512
+/// digest and used as the `seq_id` of a sequence.
+fn sha512t24u(seq: &[u8]) -> String {
+    URL_SAFE.encode(&Sha512::digest(seq)[..24])
+}
+
+/// Compute the hex-encoded MD5 digest of `seq`.
+fn md5(seq: &[u8]) -> String {
+    to_hex(&Md5::digest(seq))
+}
+
+/// Compute the hex-encoded SHA-1 digest of `seq`.
+fn sha1(seq: &[u8]) -> String {
+    to_hex(&Sha1::digest(seq))
+}
+
+/// Compute the SEGUID of `seq`, the base64 encoded SHA-1 digest without padding.
+fn seguid(seq: &[u8]) -> String {
+    STANDARD_NO_PAD.encode(Sha1::digest(seq))
+}
+
+/// Encode `bytes` as lower-case hex string.
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    /// Sequence of `NM_001304430.2`.
+    fn nm_001304430_2() -> String {
+        std::fs::read_to_string("tests/data/seqrepo/NM_001304430.2.fasta")
+            .unwrap()
+            .lines()
+            .skip(1)
+            .collect()
+    }
+
+    #[test]
+    fn digests() {
+        let seq = nm_001304430_2();
+
+        assert_eq!(
+            super::sha512t24u(seq.as_bytes()),
+            "5q5HZTCRudL17NTiv5Bn6th__0FrZH04"
+        );
+        assert_eq!(
+            super::md5(seq.as_bytes()),
+            "a8e7e4cbd2fa521b45b23692b2dd601c"
+        );
+        assert_eq!(
+            super::sha1(seq.as_bytes()),
+            "53902f297951491c09827fd9c6c6b6f3a88efec8"
+        );
+        assert_eq!(super::seguid(seq.as_bytes()), "U5AvKXlRSRwJgn/Zxsa286iO/sg");
+    }
+
+    #[test]
+    fn seq_digests() -> Result<(), anyhow::Error> {
+        // Lower-case, line-wrapped with trailing stop codon, as found in the wild.
+        let seq = nm_001304430_2().to_lowercase();
+        let wrapped = format!("{}\n{} *", &seq[..60], &seq[60..]);
+
+        assert_eq!(super::normalize_sequence(&wrapped)?, seq.to_uppercase());
+        assert_eq!(
+            super::seq_sha512t24u(&wrapped)?,
+            "5q5HZTCRudL17NTiv5Bn6th__0FrZH04"
+        );
+        assert_eq!(
+            super::seq_ga4gh_identifier(&wrapped)?,
+            "ga4gh:SQ.5q5HZTCRudL17NTiv5Bn6th__0FrZH04"
+        );
+        assert_eq!(
+            super::seq_md5(&wrapped)?,
+            "a8e7e4cbd2fa521b45b23692b2dd601c"
+        );
+        assert_eq!(
+            super::seq_sha1(&wrapped)?,
+            "53902f297951491c09827fd9c6c6b6f3a88efec8"
+        );
+        assert_eq!(super::seq_seguid(&wrapped)?, "U5AvKXlRSRwJgn/Zxsa286iO/sg");
+
+        let digests = super::SequenceDigests::new(&wrapped)?;
+        assert_eq!(digests.sha512t24u, "5q5HZTCRudL17NTiv5Bn6th__0FrZH04");
+        assert_eq!(digests.vmc_alias(), "GS_5q5HZTCRudL17NTiv5Bn6th__0FrZH04");
+        assert_eq!(
+            digests.ga4gh_identifier(),
+            "ga4gh:SQ.5q5HZTCRudL17NTiv5Bn6th__0FrZH04"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn normalize_invalid() {
+        assert!(super::normalize_sequence("ACGT-ACGT").is_err());
+        assert!(super::normalize_sequence("ACGT1").is_err());
+    }
+}
+
+// <LICENSE>
+// Copyright 2023 seqrepo-rs Contributors
+// Copyright 2016 biocommons.seqrepo Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+// </LICENSE>
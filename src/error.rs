@@ -2,6 +2,15 @@
 
 use thiserror::Error;
 
+/// Comma-separated `seq_id`s of `records` for error messages.
+fn seq_ids(records: &[crate::AliasDbRecord]) -> String {
+    records
+        .iter()
+        .map(|record| record.seqid.as_str())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 /// Error type for variant mapping.
 #[derive(Error, Debug, Clone)]
 pub enum Error {
@@ -47,8 +56,70 @@ pub enum Error {
     SeqRepoFaiQuery(String),
     #[error("could not resolve alias {0} to seqid")]
     AliasDbResolve(String),
-    #[error("alias {0} resolved to multiple seqids {1}")]
-    AliasDbResolutionAmbiguous(String, String),
+    #[error("unknown seq_id: {0}")]
+    UnknownSeqId(String),
+    #[error("alias {0} resolved to multiple seqids {}", seq_ids(.1))]
+    AliasDbResolutionAmbiguous(String, Vec<crate::AliasDbRecord>),
     #[error("problem obtaining lock on SQLite connection")]
     MutexSqlite,
+    #[error("error initializing seqrepo instance: {0}")]
+    SeqRepoInit(String),
+    #[error("error listing seqrepo instances: {0}")]
+    SeqRepoListInstances(String),
+    #[error("problem obtaining lock on FASTA reader")]
+    MutexFastaReader,
+    #[error("problem obtaining lock on pending changes")]
+    MutexPending,
+    #[error("cannot write, opened read-only")]
+    ReadOnly,
+    #[error("error reading FASTA file: {0}")]
+    SeqRepoFastaRead(String),
+    #[error("error writing FASTA file: {0}")]
+    SeqRepoFastaWrite(String),
+    #[error("invalid sequence: {0}")]
+    InvalidSequence(String),
+    #[error("invalid identifier: {0}")]
+    InvalidIdentifier(String),
+    #[error("invalid region: {0}")]
+    InvalidRegion(String),
+    #[error("invalid range {1}..{2} for sequence {0}, begin is after end")]
+    InvalidRange(String, usize, usize),
+    #[error("range {1}..{2} out of bounds for sequence {0} of length {3}")]
+    RangeOutOfBounds(String, usize, usize, usize),
+    #[error("unknown genetic code: {0}")]
+    UnknownGeneticCode(u8),
+    #[error("stop codon at codon {0} before the end of the translation")]
+    TranslationStopCodon(usize),
+    #[error("incomplete codon of {0} bases at the end of the translation")]
+    TranslationPartialCodon(usize),
+    #[error("error joining blocking task: {0}")]
+    AsyncJoin(String),
+    #[error("error running server: {0}")]
+    Server(String),
+    #[error("error writing output: {0}")]
+    WriteOutput(String),
+    #[error("sequence not found on refget server: {0}")]
+    RefgetNotFound(String),
+    #[error("error on refget request: {0}")]
+    RefgetRequest(String),
+    #[error("refget server responded with status {0}: {1}")]
+    RefgetStatus(u16, String),
+    #[error("invalid response from refget server: {0}")]
+    RefgetResponse(String),
+}
+
+impl Error {
+    /// Whether the error means that a sequence or alias is unknown.
+    ///
+    /// Other errors, e.g., I/O errors or ambiguous aliases, are hard failures.  This
+    /// is used by `FallbackSeqRepo` to decide whether to try the next repository.
+    pub fn is_not_found(&self) -> bool {
+        matches!(
+            self,
+            Error::AliasDbResolve(_)
+                | Error::UnknownSeqId(_)
+                | Error::SeqSepoCacheKey(_)
+                | Error::RefgetNotFound(_)
+        )
+    }
 }
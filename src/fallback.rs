@@ -0,0 +1,359 @@
+//! Combination of sequence repositories that are tried in order.
+
+use crate::error::Error;
+use crate::interface::{AliasOrSeqId, Interface, SequenceInfo};
+use crate::Strand;
+
+/// Sequence repository trying `primary` first and `fallback` if the sequence is unknown.
+///
+/// Only errors for which `Error::is_not_found()` holds lead to trying `fallback`,
+/// other errors are returned as they are.  Longer chains can be built by nesting,
+/// e.g., `FallbackSeqRepo::new(cache, FallbackSeqRepo::new(seq_repo, refget))` to try
+/// a cache, then a local `SeqRepo`, then a remote `RefgetSeqRepo`.  Repositories of the
+/// same type can be combined with `FallbackChainSeqRepo` instead.
+#[derive(Debug, Clone)]
+pub struct FallbackSeqRepo<A, B> {
+    /// The repository tried first.
+    primary: A,
+    /// The repository tried if `primary` does not know the sequence.
+    fallback: B,
+}
+
+impl<A, B> FallbackSeqRepo<A, B>
+where
+    A: Interface,
+    B: Interface,
+{
+    /// Combine `primary` and `fallback`.
+    pub fn new(primary: A, fallback: B) -> Self {
+        Self { primary, fallback }
+    }
+
+    /// Provide access to the repository tried first.
+    pub fn primary(&self) -> &A {
+        &self.primary
+    }
+
+    /// Provide access to the repository tried second.
+    pub fn fallback(&self) -> &B {
+        &self.fallback
+    }
+
+    /// Run `f` on `primary` and on `fallback` if the result is "not found".
+    fn try_both<T, F>(&self, f: F) -> Result<T, Error>
+    where
+        F: Fn(&dyn Interface) -> Result<T, Error>,
+    {
+        try_in_order([&self.primary as &dyn Interface, &self.fallback], f)
+    }
+}
+
+impl<A, B> Interface for FallbackSeqRepo<A, B>
+where
+    A: Interface,
+    B: Interface,
+{
+    fn fetch_sequence_part(
+        &self,
+        alias_or_seq_id: &AliasOrSeqId,
+        begin: Option<usize>,
+        end: Option<usize>,
+    ) -> Result<String, Error> {
+        self.try_both(|repo| repo.fetch_sequence_part(alias_or_seq_id, begin, end))
+    }
+
+    fn fetch_sequence_part_stranded(
+        &self,
+        alias_or_seq_id: &AliasOrSeqId,
+        begin: Option<usize>,
+        end: Option<usize>,
+        strand: Strand,
+    ) -> Result<String, Error> {
+        self.try_both(|repo| repo.fetch_sequence_part_stranded(alias_or_seq_id, begin, end, strand))
+    }
+
+    fn sequence_info(&self, alias_or_seq_id: &AliasOrSeqId) -> Result<SequenceInfo, Error> {
+        self.try_both(|repo| repo.sequence_info(alias_or_seq_id))
+    }
+
+    fn find_sequence(&self, seq: &str) -> Result<Option<String>, Error> {
+        find_in_order([&self.primary as &dyn Interface, &self.fallback], seq)
+    }
+}
+
+/// Sequence repository trying any number of repositories of the same type in order.
+///
+/// This is the counterpart of `FallbackSeqRepo` for a list of repositories, e.g., of
+/// several `SeqRepo` instances, with the same handling of "not found" errors.
+#[derive(Debug, Clone)]
+pub struct FallbackChainSeqRepo<T> {
+    /// The repositories, in the order they are tried.
+    repos: Vec<T>,
+}
+
+impl<T> FallbackChainSeqRepo<T>
+where
+    T: Interface,
+{
+    /// Combine `repos`, to be tried in the given order.
+    pub fn new(repos: Vec<T>) -> Result<Self, Error> {
+        if repos.is_empty() {
+            return Err(Error::SeqRepoInit(
+                "fallback chain requires at least one repository".to_string(),
+            ));
+        }
+        Ok(Self { repos })
+    }
+
+    /// Provide access to the repositories, in the order they are tried.
+    pub fn repos(&self) -> &[T] {
+        &self.repos
+    }
+
+    /// Run `f` on the repositories until the result is not "not found".
+    fn try_each<U, F>(&self, f: F) -> Result<U, Error>
+    where
+        F: Fn(&dyn Interface) -> Result<U, Error>,
+    {
+        try_in_order(self.repos.iter().map(|repo| repo as &dyn Interface), f)
+    }
+}
+
+impl<T> Interface for FallbackChainSeqRepo<T>
+where
+    T: Interface,
+{
+    fn fetch_sequence_part(
+        &self,
+        alias_or_seq_id: &AliasOrSeqId,
+        begin: Option<usize>,
+        end: Option<usize>,
+    ) -> Result<String, Error> {
+        self.try_each(|repo| repo.fetch_sequence_part(alias_or_seq_id, begin, end))
+    }
+
+    fn fetch_sequence_part_stranded(
+        &self,
+        alias_or_seq_id: &AliasOrSeqId,
+        begin: Option<usize>,
+        end: Option<usize>,
+        strand: Strand,
+    ) -> Result<String, Error> {
+        self.try_each(|repo| repo.fetch_sequence_part_stranded(alias_or_seq_id, begin, end, strand))
+    }
+
+    fn sequence_info(&self, alias_or_seq_id: &AliasOrSeqId) -> Result<SequenceInfo, Error> {
+        self.try_each(|repo| repo.sequence_info(alias_or_seq_id))
+    }
+
+    fn find_sequence(&self, seq: &str) -> Result<Option<String>, Error> {
+        find_in_order(self.repos.iter().map(|repo| repo as &dyn Interface), seq)
+    }
+}
+
+/// Run `f` on `repos` in order, until the result is not "not found".
+///
+/// Returns the result of the last repository if all fail with "not found".
+fn try_in_order<'a, T, F>(
+    repos: impl IntoIterator<Item = &'a dyn Interface>,
+    f: F,
+) -> Result<T, Error>
+where
+    F: Fn(&dyn Interface) -> Result<T, Error>,
+{
+    let mut last = None;
+    for repo in repos {
+        match f(repo) {
+            Err(e) if e.is_not_found() => last = Some(Err(e)),
+            result => return result,
+        }
+    }
+    last.expect("at least one repository")
+}
+
+/// Return the `seq_id` of `seq` from the first of `repos` that contains it.
+fn find_in_order<'a>(
+    repos: impl IntoIterator<Item = &'a dyn Interface>,
+    seq: &str,
+) -> Result<Option<String>, Error> {
+    for repo in repos {
+        if let Some(seq_id) = repo.find_sequence(seq)? {
+            return Ok(Some(seq_id));
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use pretty_assertions::assert_eq;
+
+    use super::{FallbackChainSeqRepo, FallbackSeqRepo};
+    use crate::{AliasOrSeqId, Error, Interface, SeqRepo, SequenceInfo, Strand};
+
+    /// Repository failing all calls with the given error, counting the calls.
+    struct Failing {
+        error: Error,
+        calls: AtomicUsize,
+    }
+
+    impl Failing {
+        fn new(error: Error) -> Self {
+            Self {
+                error,
+                calls: AtomicUsize::new(0),
+            }
+        }
+    }
+
+    impl Interface for Failing {
+        fn fetch_sequence_part(
+            &self,
+            _alias_or_seq_id: &AliasOrSeqId,
+            _begin: Option<usize>,
+            _end: Option<usize>,
+        ) -> Result<String, Error> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Err(self.error.clone())
+        }
+
+        fn sequence_info(&self, _alias_or_seq_id: &AliasOrSeqId) -> Result<SequenceInfo, Error> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Err(self.error.clone())
+        }
+    }
+
+    #[test]
+    fn test_sync() {
+        fn is_sync<T: Sync>() {}
+        is_sync::<super::FallbackSeqRepo<SeqRepo, SeqRepo>>();
+        is_sync::<super::FallbackChainSeqRepo<SeqRepo>>();
+    }
+
+    #[test]
+    fn fallback_if_not_found() -> Result<(), anyhow::Error> {
+        let aos: AliasOrSeqId = "refseq:NM_001304430.2".parse()?;
+        for error in [
+            Error::AliasDbResolve("NM_001304430.2".to_string()),
+            Error::UnknownSeqId("5q5HZTCRudL17NTiv5Bn6th__0FrZH04".to_string()),
+            Error::SeqSepoCacheKey("NM_001304430.2:0-10".to_string()),
+            Error::RefgetNotFound("refseq:NM_001304430.2".to_string()),
+        ] {
+            let repo = FallbackSeqRepo::new(
+                Failing::new(error),
+                SeqRepo::new("tests/data/seqrepo", "latest")?,
+            );
+            assert_eq!(
+                repo.fetch_sequence_part(&aos, Some(0), Some(10))?,
+                "ACTGCTGAGC"
+            );
+            assert_eq!(
+                repo.fetch_sequence_part_stranded(&aos, Some(0), Some(10), Strand::Reverse)?,
+                "GCTCAGCAGT"
+            );
+            assert_eq!(repo.sequence_info(&aos)?.len, 1873);
+            assert_eq!(repo.primary().calls.load(Ordering::SeqCst), 3);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn hard_failure() -> Result<(), anyhow::Error> {
+        let aos: AliasOrSeqId = "refseq:NM_001304430.2".parse()?;
+        let repo = FallbackSeqRepo::new(
+            Failing::new(Error::SeqRepoFaiQuery("broken".to_string())),
+            SeqRepo::new("tests/data/seqrepo", "latest")?,
+        );
+        assert!(matches!(
+            repo.fetch_sequence(&aos),
+            Err(Error::SeqRepoFaiQuery(_))
+        ));
+        assert!(matches!(
+            repo.sequence_info(&aos),
+            Err(Error::SeqRepoFaiQuery(_))
+        ));
+
+        // Not found in either repository.
+        let repo = FallbackSeqRepo::new(
+            SeqRepo::new("tests/data/seqrepo", "latest")?,
+            Failing::new(Error::RefgetNotFound("NM_000000.0".to_string())),
+        );
+        assert!(matches!(
+            repo.fetch_sequence(&"NM_000000.0".parse()?),
+            Err(Error::RefgetNotFound(_))
+        ));
+        assert_eq!(repo.fallback().calls.load(Ordering::SeqCst), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn chain() -> Result<(), anyhow::Error> {
+        let aos: AliasOrSeqId = "refseq:NM_001304430.2".parse()?;
+        let repo = FallbackChainSeqRepo::new(vec![
+            Failing::new(Error::AliasDbResolve("NM_001304430.2".to_string())),
+            Failing::new(Error::RefgetNotFound("refseq:NM_001304430.2".to_string())),
+            Failing::new(Error::SeqRepoFaiQuery("broken".to_string())),
+            Failing::new(Error::RefgetNotFound("refseq:NM_001304430.2".to_string())),
+        ])?;
+        assert!(matches!(
+            repo.fetch_sequence(&aos),
+            Err(Error::SeqRepoFaiQuery(_))
+        ));
+        let calls: Vec<_> = repo
+            .repos()
+            .iter()
+            .map(|repo| repo.calls.load(Ordering::SeqCst))
+            .collect();
+        assert_eq!(calls, vec![1, 1, 1, 0]);
+
+        // The error of the last repository is returned if none knows the sequence.
+        let repo = FallbackChainSeqRepo::new(vec![
+            Failing::new(Error::AliasDbResolve("NM_001304430.2".to_string())),
+            Failing::new(Error::RefgetNotFound("refseq:NM_001304430.2".to_string())),
+        ])?;
+        assert!(matches!(
+            repo.sequence_info(&aos),
+            Err(Error::RefgetNotFound(_))
+        ));
+
+        assert!(FallbackChainSeqRepo::<SeqRepo>::new(Vec::new()).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn find_sequence() -> Result<(), anyhow::Error> {
+        let sr = SeqRepo::new("tests/data/seqrepo", "latest")?;
+        let seq = sr.fetch_sequence(&"refseq:NM_001304430.2".parse()?)?;
+
+        let repo = FallbackSeqRepo::new(Failing::new(Error::SeqSepoCacheKey("x".to_string())), sr);
+        assert_eq!(
+            repo.find_sequence(&seq)?,
+            Some("5q5HZTCRudL17NTiv5Bn6th__0FrZH04".to_string())
+        );
+        assert_eq!(repo.find_sequence("ACGT")?, None);
+
+        Ok(())
+    }
+}
+
+// <LICENSE>
+// Copyright 2023 seqrepo-rs Contributors
+// Copyright 2016 biocommons.seqrepo Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+// </LICENSE>
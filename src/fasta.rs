@@ -1,18 +1,483 @@
 //! Code for supporting the FASTA directory access.
 
 use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque},
+    fs::{File, OpenOptions},
+    io::{BufRead, BufWriter, Read, Seek, SeekFrom, Write},
+    ops::Range,
     path::{Path, PathBuf},
     sync::{Arc, Mutex},
 };
 
 use chrono::NaiveDateTime;
-use noodles::core::{Position, Region};
 use rusqlite::{Connection, OpenFlags};
 
 use crate::error::Error;
+use crate::pool::{default_pool_size, ConnectionPool};
+use crate::schema::{create_database, SEQUENCES_SCHEMA};
 
 static EXPECTED_SCHEMA_VERSION: u32 = 1;
 
+/// Maximal gap between ranges that `FastaDir::fetch_sequence_parts()` reads in one
+/// pass, about the size of one BGZF block.
+static MERGE_GAP: usize = 65_536;
+
+/// Number of bases per line in the FASTA files written by `FastaDir`.
+static LINE_BASES: usize = 100;
+
+/// Streaming reader over a range of bases of a sequence in a FASTA file.
+///
+/// Obtained from `FastaDir::sequence_reader()`.  The position of each base is
+/// computed from the line geometry in the FAI record, so only the required part of
+/// the underlying BGZF file is decompressed and line terminators are skipped.
+pub struct SequenceReader<R = noodles::bgzf::IndexedReader<File>> {
+    /// The underlying reader, positioned at the next base or line terminator.
+    inner: R,
+    /// Number of bases per line.
+    line_bases: u64,
+    /// Number of bytes of the line terminators.
+    line_terminator: u64,
+    /// Number of bases left to read in total.
+    remaining: u64,
+    /// Number of bases left to read in the current line.
+    line_remaining: u64,
+}
+
+impl<R> SequenceReader<R>
+where
+    R: BufRead + Seek,
+{
+    /// Create reader over the bases `start..end` of the sequence at `layout` in `inner`.
+    pub(crate) fn new(
+        mut inner: R,
+        layout: &FaiLayout,
+        start: u64,
+        end: u64,
+    ) -> std::io::Result<Self> {
+        let line_bases = layout.line_bases;
+        let offset = layout.offset + (start / line_bases) * layout.line_width + start % line_bases;
+        inner.seek(SeekFrom::Start(offset))?;
+
+        Ok(Self {
+            inner,
+            line_bases,
+            line_terminator: layout.line_width - line_bases,
+            remaining: end.saturating_sub(start),
+            line_remaining: line_bases - start % line_bases,
+        })
+    }
+}
+
+impl<R> SequenceReader<R> {
+    /// Number of bases left to read.
+    pub fn remaining(&self) -> u64 {
+        self.remaining
+    }
+}
+
+impl<R> SequenceReader<R>
+where
+    R: BufRead,
+{
+    /// Skip the line terminator at the current position.
+    fn skip_line_terminator(&mut self) -> std::io::Result<()> {
+        let mut skip = self.line_terminator as usize;
+        while skip > 0 {
+            let available = self.inner.fill_buf()?.len();
+            if available == 0 {
+                return Err(std::io::ErrorKind::UnexpectedEof.into());
+            }
+            let n = std::cmp::min(available, skip);
+            self.inner.consume(n);
+            skip -= n;
+        }
+        Ok(())
+    }
+}
+
+impl<R> Read for SequenceReader<R>
+where
+    R: BufRead,
+{
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = {
+            let available = self.fill_buf()?;
+            let n = std::cmp::min(available.len(), buf.len());
+            buf[..n].copy_from_slice(&available[..n]);
+            n
+        };
+        self.consume(n);
+        Ok(n)
+    }
+}
+
+impl<R> BufRead for SequenceReader<R>
+where
+    R: BufRead,
+{
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        if self.remaining == 0 {
+            return Ok(&[]);
+        }
+        if self.line_remaining == 0 {
+            self.skip_line_terminator()?;
+            self.line_remaining = self.line_bases;
+        }
+
+        let n = std::cmp::min(self.remaining, self.line_remaining) as usize;
+        let available = self.inner.fill_buf()?;
+        if available.is_empty() {
+            return Err(std::io::ErrorKind::UnexpectedEof.into());
+        }
+        Ok(&available[..std::cmp::min(n, available.len())])
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.inner.consume(amt);
+        self.remaining -= amt as u64;
+        self.line_remaining -= amt as u64;
+    }
+}
+
+impl<R> std::fmt::Debug for SequenceReader<R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SequenceReader")
+            .field("remaining", &self.remaining)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Position and line layout of a sequence in a FASTA file, as given in its FAI record.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct FaiLayout {
+    /// Offset of the first base in the file.
+    offset: u64,
+    /// Number of bases per line.
+    line_bases: u64,
+    /// Number of bytes per line, including the line terminator.
+    line_width: u64,
+}
+
+/// Find the layout of `seq_id` in the FAI `index`.
+fn find_fai_layout(index: &noodles::fasta::fai::Index, seq_id: &str) -> Result<FaiLayout, Error> {
+    index
+        .iter()
+        .find(|record| record.name() == seq_id.as_bytes())
+        .map(|record| FaiLayout {
+            offset: record.offset(),
+            line_bases: record.line_bases(),
+            line_width: record.line_width(),
+        })
+        .ok_or_else(|| Error::SeqRepoFaiQuery(format!("no FAI record for {seq_id}")))
+}
+
+/// Merge the `ranges` at `indices`, sorted by start, into spans to read at once.
+///
+/// Ranges are merged if the gap to the span is at most `MERGE_GAP` and the merged
+/// span is at most `max_span` long.  Returns the spans with the positions in
+/// `indices` of their ranges.
+fn merge_ranges(
+    ranges: &[Range<usize>],
+    indices: &[usize],
+    max_span: usize,
+) -> Vec<(Range<usize>, Range<usize>)> {
+    let mut result = Vec::new();
+    let mut first = 0;
+    while first < indices.len() {
+        let span_start = ranges[indices[first]].start;
+        let mut span_end = ranges[indices[first]].end;
+        let mut last = first + 1;
+        while last < indices.len() {
+            let range = &ranges[indices[last]];
+            let end = std::cmp::max(span_end, range.end);
+            if range.start > span_end + MERGE_GAP || end - span_start > max_span {
+                break;
+            }
+            span_end = end;
+            last += 1;
+        }
+        result.push((span_start..span_end, first..last));
+        first = last;
+    }
+    result
+}
+
+/// Indexed FASTA reader on top of an indexed BGZF reader.
+type FastaReader = noodles::fasta::IndexedReader<noodles::bgzf::IndexedReader<File>>;
+
+/// Policy for evicting open readers from the pool of a `FastaDir`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum EvictionPolicy {
+    /// Evict the reader that has not been used for the longest time.
+    #[default]
+    LeastRecentlyUsed,
+    /// Evict the reader that has been opened first.
+    FirstInFirstOut,
+}
+
+/// Configuration of a `FastaDir`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FastaDirConfig {
+    /// Maximal number of opened indexed readers to keep, `0` disables pooling.
+    pub reader_pool_capacity: usize,
+    /// Policy for evicting readers when the pool is full.
+    pub eviction_policy: EvictionPolicy,
+    /// Whether to open the database for writing.
+    pub writeable: bool,
+    /// Maximal number of read-only connections to the database, defaults to the
+    /// available parallelism.  Writeable databases use a single connection.
+    pub connection_pool_size: usize,
+    /// Whether to clamp requested ranges to the sequence instead of failing.
+    ///
+    /// By default, ranges with `begin > end` or `end` past the sequence length are
+    /// rejected.  In lenient mode, both ends are clamped to the sequence length and
+    /// empty ranges give empty sequences.
+    pub lenient_ranges: bool,
+    /// Maximal length of the spans that `fetch_sequence_parts()` reads at once.
+    ///
+    /// Close ranges are merged into one span as long as it does not exceed this
+    /// length, single ranges longer than this are still read at once.
+    pub max_merged_span: usize,
+}
+
+impl Default for FastaDirConfig {
+    fn default() -> Self {
+        Self {
+            reader_pool_capacity: 16,
+            eviction_policy: Default::default(),
+            writeable: false,
+            connection_pool_size: default_pool_size(),
+            lenient_ranges: false,
+            max_merged_span: 1 << 20,
+        }
+    }
+}
+
+/// Bounded pool of opened readers, keyed by `relpath`.
+///
+/// The pool is expected to be small, so entries are kept in a `VecDeque` ordered
+/// such that the entry to evict next is at the front.
+struct ReaderPool<T> {
+    /// Maximal number of entries.
+    capacity: usize,
+    /// Policy for evicting entries.
+    eviction_policy: EvictionPolicy,
+    /// The pooled entries.
+    entries: VecDeque<(String, Arc<Mutex<T>>)>,
+}
+
+impl<T> ReaderPool<T> {
+    fn new(capacity: usize, eviction_policy: EvictionPolicy) -> Self {
+        Self {
+            capacity,
+            eviction_policy,
+            entries: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// Get entry for `key`, if any.
+    fn get(&mut self, key: &str) -> Option<Arc<Mutex<T>>> {
+        let idx = self.entries.iter().position(|(k, _)| k == key)?;
+        match self.eviction_policy {
+            EvictionPolicy::LeastRecentlyUsed => {
+                let entry = self.entries.remove(idx)?;
+                let value = entry.1.clone();
+                self.entries.push_back(entry);
+                Some(value)
+            }
+            EvictionPolicy::FirstInFirstOut => Some(self.entries[idx].1.clone()),
+        }
+    }
+
+    /// Insert `value` for `key`, evicting entries if necessary.
+    ///
+    /// If another value has been inserted for `key` in the meantime, that value is
+    /// returned and `value` is dropped.
+    fn insert(&mut self, key: &str, value: T) -> Arc<Mutex<T>> {
+        if let Some(existing) = self.get(key) {
+            return existing;
+        }
+        let value = Arc::new(Mutex::new(value));
+        if self.capacity > 0 {
+            while self.entries.len() >= self.capacity {
+                self.entries.pop_front();
+            }
+            self.entries.push_back((key.to_string(), value.clone()));
+        }
+        value
+    }
+}
+
+impl<T> std::fmt::Debug for ReaderPool<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ReaderPool")
+            .field("capacity", &self.capacity)
+            .field("eviction_policy", &self.eviction_policy)
+            .field(
+                "keys",
+                &self.entries.iter().map(|(k, _)| k).collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+/// A sequence written to the pending file but not committed yet.
+#[derive(Debug)]
+struct PendingRecord {
+    seq_id: String,
+    len: usize,
+    alpha: String,
+    /// Uncompressed offset of the first base in the file.
+    offset: u64,
+}
+
+/// BGZF compressed FASTA file that sequences are currently written to.
+struct PendingFile {
+    /// Path relative to the root of the `FastaDir`.
+    relpath: String,
+    /// Writer to the file.
+    writer: noodles::bgzf::Writer<File>,
+    /// Number of uncompressed bytes written so far.
+    offset: u64,
+    /// The records written so far.
+    records: Vec<PendingRecord>,
+    /// The `seq_id`s of `records`.
+    seq_ids: HashSet<String>,
+}
+
+impl PendingFile {
+    /// Create new file with a dated path below `root_dir`.
+    fn create(root_dir: &Path) -> Result<Self, Error> {
+        let now = chrono::Utc::now();
+        let relpath = format!(
+            "{}/{}.{:06}.fa.bgz",
+            now.format("%Y/%m%d/%H%M"),
+            now.timestamp(),
+            now.timestamp_subsec_micros()
+        );
+        let path = root_dir.join(&relpath);
+        std::fs::create_dir_all(path.parent().expect("relpath has parent"))
+            .map_err(|e| Error::SeqRepoFastaWrite(e.to_string()))?;
+        let file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)
+            .map_err(|e| Error::SeqRepoFastaWrite(e.to_string()))?;
+
+        Ok(Self {
+            relpath,
+            writer: noodles::bgzf::Writer::new(file),
+            offset: 0,
+            records: Vec::new(),
+            seq_ids: HashSet::new(),
+        })
+    }
+
+    /// Write the sequence `seq` with the given `seq_id`.
+    fn write(&mut self, seq_id: &str, seq: &str) -> std::io::Result<()> {
+        let header = format!(">{seq_id}\n");
+        self.writer.write_all(header.as_bytes())?;
+        self.offset += header.len() as u64;
+
+        let offset = self.offset;
+        for line in seq.as_bytes().chunks(LINE_BASES) {
+            self.writer.write_all(line)?;
+            self.writer.write_all(b"\n")?;
+            self.offset += line.len() as u64 + 1;
+        }
+
+        self.records.push(PendingRecord {
+            seq_id: seq_id.to_string(),
+            len: seq.len(),
+            alpha: seq.chars().collect::<BTreeSet<_>>().into_iter().collect(),
+            offset,
+        });
+        self.seq_ids.insert(seq_id.to_string());
+        Ok(())
+    }
+
+    /// Finish writing the file and write the FAI and GZI indices next to it.
+    fn finish(self, root_dir: &Path) -> Result<Vec<PendingRecord>, Error> {
+        let path = root_dir.join(&self.relpath);
+        self.writer
+            .finish()
+            .map_err(|e| Error::SeqRepoFastaWrite(e.to_string()))?;
+        write_fai(
+            &PathBuf::from(format!("{}.fai", path.display())),
+            &self.records,
+        )
+        .map_err(|e| Error::SeqRepoFastaWrite(e.to_string()))?;
+        write_gzi(&path, &PathBuf::from(format!("{}.gzi", path.display())))
+            .map_err(|e| Error::SeqRepoFastaWrite(e.to_string()))?;
+        Ok(self.records)
+    }
+}
+
+impl std::fmt::Debug for PendingFile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PendingFile")
+            .field("relpath", &self.relpath)
+            .field("offset", &self.offset)
+            .field("records", &self.records)
+            .finish()
+    }
+}
+
+/// Write FAI index for the given records.
+fn write_fai(path: &Path, records: &[PendingRecord]) -> std::io::Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    for record in records {
+        writeln!(
+            writer,
+            "{}\t{}\t{}\t{}\t{}",
+            record.seq_id,
+            record.len,
+            record.offset,
+            LINE_BASES,
+            LINE_BASES + 1
+        )?;
+    }
+    writer.flush()
+}
+
+/// Write GZI index for the BGZF file at `path_bgzip`.
+///
+/// The index lists compressed and uncompressed offsets of all non-empty blocks but the
+/// first one, as written by `bgzip -i`.
+fn write_gzi(path_bgzip: &Path, path_gzi: &Path) -> std::io::Result<()> {
+    let mut file = File::open(path_bgzip)?;
+    let file_len = file.metadata()?.len();
+
+    let mut entries = Vec::new();
+    let (mut compressed, mut uncompressed) = (0u64, 0u64);
+    let mut header = [0u8; 18];
+    let mut isize = [0u8; 4];
+    while compressed < file_len {
+        // The header is followed by `BSIZE`, the total block size minus one.
+        file.seek(SeekFrom::Start(compressed))?;
+        file.read_exact(&mut header)?;
+        let block_size = u16::from_le_bytes([header[16], header[17]]) as u64 + 1;
+        // The block ends with `ISIZE`, the uncompressed size of the block.
+        file.seek(SeekFrom::Start(compressed + block_size - 4))?;
+        file.read_exact(&mut isize)?;
+        let block_len = u32::from_le_bytes(isize) as u64;
+
+        if compressed > 0 && block_len > 0 {
+            entries.push((compressed, uncompressed));
+        }
+        compressed += block_size;
+        uncompressed += block_len;
+    }
+
+    let mut writer = BufWriter::new(File::create(path_gzi)?);
+    writer.write_all(&(entries.len() as u64).to_le_bytes())?;
+    for (compressed, uncompressed) in entries {
+        writer.write_all(&compressed.to_le_bytes())?;
+        writer.write_all(&uncompressed.to_le_bytes())?;
+    }
+    writer.flush()
+}
+
 /// A record from the `db.sqlite3` database.
 #[derive(Debug, PartialEq)]
 pub struct SeqInfoRecord {
@@ -37,29 +502,46 @@ pub struct SeqInfoRecord {
 pub struct FastaDir {
     /// The path to the directory ("$instance/sequences" within seqrepo).
     root_dir: PathBuf,
-    /// Connection to the SQLite database "db.sqlite3" inside root_dir.
-    conn: Arc<Mutex<Connection>>,
+    /// Connections to the SQLite database "db.sqlite3" inside root_dir.
+    pool: ConnectionPool,
     /// Schema version.
     schema_version: u32,
+    /// Configuration of the `FastaDir`.
+    config: FastaDirConfig,
+    /// Pool of opened indexed readers, keyed by `relpath`.
+    readers: Mutex<ReaderPool<FastaReader>>,
+    /// File that stored sequences are written to until the next commit.
+    pending: Mutex<Option<PendingFile>>,
 }
 
 impl FastaDir {
     /// Initialize new `FastaDir`, will open connection to the database.
     pub fn new<P>(root_dir: P) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+    {
+        Self::new_with_config(root_dir, Default::default())
+    }
+
+    /// Initialize new `FastaDir` with the given configuration.
+    pub fn new_with_config<P>(root_dir: P, config: FastaDirConfig) -> Result<Self, Error>
     where
         P: AsRef<Path>,
     {
         let root_dir = PathBuf::from(root_dir.as_ref());
 
         let db_path = root_dir.join("db.sqlite3");
-        let conn = Connection::open_with_flags(
-            db_path,
-            OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
-        )
-        .map_err(|e| Error::AliasDbConnect(e.to_string()))?;
+        let (flags, capacity) = if config.writeable {
+            (OpenFlags::SQLITE_OPEN_READ_WRITE, 1)
+        } else {
+            (
+                OpenFlags::SQLITE_OPEN_READ_ONLY,
+                config.connection_pool_size,
+            )
+        };
+        let pool = ConnectionPool::new(&db_path, flags, capacity, Error::AliasDbConnect)?;
 
-        let schema_version = Self::fetch_schema_version(&conn)?;
-        let conn = Arc::new(Mutex::new(conn));
+        let schema_version = Self::fetch_schema_version(&*pool.get()?)?;
         if schema_version != EXPECTED_SCHEMA_VERSION {
             Err(Error::SeqSepoDbSchemaVersion(
                 schema_version,
@@ -68,12 +550,42 @@ impl FastaDir {
         } else {
             Ok(FastaDir {
                 root_dir,
-                conn,
+                pool,
                 schema_version,
+                readers: Mutex::new(ReaderPool::new(
+                    config.reader_pool_capacity,
+                    config.eviction_policy,
+                )),
+                config,
+                pending: Default::default(),
             })
         }
     }
 
+    /// Create a new, empty `FastaDir` at `root_dir` and open it.
+    ///
+    /// The directory is created if necessary but must not contain a database yet.
+    pub fn init<P>(root_dir: P) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+    {
+        std::fs::create_dir_all(root_dir.as_ref())
+            .map_err(|e| Error::SeqRepoInit(e.to_string()))?;
+        create_database(
+            &root_dir.as_ref().join("db.sqlite3"),
+            SEQUENCES_SCHEMA,
+            EXPECTED_SCHEMA_VERSION,
+        )
+        .map_err(|e| Error::SeqRepoDbExec(e.to_string()))?;
+        Self::new_with_config(
+            root_dir,
+            FastaDirConfig {
+                writeable: true,
+                ..Default::default()
+            },
+        )
+    }
+
     /// Load schema version from the database.
     fn fetch_schema_version(conn: &Connection) -> Result<u32, Error> {
         let sql = "select value from meta where key = 'schema version'";
@@ -93,9 +605,82 @@ impl FastaDir {
         self.schema_version
     }
 
+    /// Whether the database has been opened for writing.
+    pub fn writeable(&self) -> bool {
+        self.config.writeable
+    }
+
+    /// Whether a sequence with the given `seq_id` has been committed to the database.
+    pub fn contains(&self, seq_id: &str) -> Result<bool, Error> {
+        let locked_conn = self.pool.get()?;
+
+        locked_conn
+            .query_row(
+                "select count(*) from seqinfo where seq_id = ?",
+                [seq_id],
+                |row| row.get::<_, u64>(0),
+            )
+            .map(|count| count > 0)
+            .map_err(|e| Error::SeqRepoDbExec(e.to_string()))
+    }
+
+    /// Store the sequence `seq` with the given `seq_id`.
+    ///
+    /// Sequences are written to a new dated BGZF file that is indexed and registered
+    /// in the database on the next call to `commit()`.  Returns `false` if a sequence
+    /// with `seq_id` has already been stored.
+    pub fn store(&self, seq_id: &str, seq: &str) -> Result<bool, Error> {
+        if !self.config.writeable {
+            return Err(Error::ReadOnly);
+        }
+
+        let mut pending = self.pending.lock().map_err(|_| Error::MutexPending)?;
+        let is_pending = pending
+            .as_ref()
+            .map(|file| file.seq_ids.contains(seq_id))
+            .unwrap_or(false);
+        if is_pending || self.contains(seq_id)? {
+            return Ok(false);
+        }
+
+        if pending.is_none() {
+            *pending = Some(PendingFile::create(&self.root_dir)?);
+        }
+        pending
+            .as_mut()
+            .expect("pending file created above")
+            .write(seq_id, seq)
+            .map_err(|e| Error::SeqRepoFastaWrite(e.to_string()))?;
+
+        Ok(true)
+    }
+
+    /// Finish the pending file and register its sequences in the database.
+    pub fn commit(&self) -> Result<(), Error> {
+        let mut pending = self.pending.lock().map_err(|_| Error::MutexPending)?;
+        let Some(file) = pending.take() else {
+            return Ok(());
+        };
+        let relpath = file.relpath.clone();
+        let records = file.finish(&self.root_dir)?;
+
+        let mut locked_conn = self.pool.get()?;
+        let tx = locked_conn
+            .transaction()
+            .map_err(|e| Error::SeqRepoDbExec(e.to_string()))?;
+        for record in &records {
+            tx.execute(
+                "insert into seqinfo (seq_id, len, alpha, relpath) values (?, ?, ?, ?)",
+                (&record.seq_id, record.len, &record.alpha, &relpath),
+            )
+            .map_err(|e| Error::SeqRepoDbExec(e.to_string()))?;
+        }
+        tx.commit().map_err(|e| Error::SeqRepoDbExec(e.to_string()))
+    }
+
     /// Load `SeqInfoRecord` from database.
     pub fn fetch_seqinfo(&self, seq_id: &str) -> Result<SeqInfoRecord, Error> {
-        let locked_conn = self.conn.lock().map_err(|_| Error::MutexSqlite)?;
+        let locked_conn = self.pool.get()?;
 
         let sql = "select seq_id, len, alpha, added, relpath from seqinfo \
         where seq_id = ? order by added desc";
@@ -116,7 +701,10 @@ impl FastaDir {
                 relpath: row.get(4)?,
             })
         })
-        .map_err(|e| Error::SeqRepoDbExec(e.to_string()))
+        .map_err(|e| match e {
+            rusqlite::Error::QueryReturnedNoRows => Error::UnknownSeqId(seq_id.to_string()),
+            e => Error::SeqRepoDbExec(e.to_string()),
+        })
     }
 
     /// Load complete sequence from FASTA directory.
@@ -131,49 +719,229 @@ impl FastaDir {
         begin: Option<usize>,
         end: Option<usize>,
     ) -> Result<String, Error> {
+        let mut buf = Vec::new();
+        self.fetch_sequence_part_into(seq_id, begin, end, &mut buf)?;
+        String::from_utf8(buf).map_err(|e| Error::SeqRepoFastaRead(e.to_string()))
+    }
+
+    /// Load sequence fragment from FASTA directory, appending its bytes to `buf`.
+    ///
+    /// The bases are read directly from the BGZF file using the FAI index, without
+    /// intermediate copies or UTF-8 validation.
+    pub fn fetch_sequence_part_into(
+        &self,
+        seq_id: &str,
+        begin: Option<usize>,
+        end: Option<usize>,
+        buf: &mut Vec<u8>,
+    ) -> Result<(), Error> {
+        let seqinfo = self.fetch_seqinfo(seq_id)?;
+        let Range { start, end } = self.check_range(&seqinfo, begin, end)?;
+        if start == end {
+            return Ok(());
+        }
+
+        let reader = self.reader(&seqinfo.relpath)?;
+        let mut fai_reader = reader.lock().map_err(|_| Error::MutexFastaReader)?;
+        let fai_layout = find_fai_layout(fai_reader.index(), seq_id)?;
+
+        SequenceReader::new(fai_reader.get_mut(), &fai_layout, start as u64, end as u64)
+            .and_then(|mut reader| reader.read_to_end(buf))
+            .map(|_| ())
+            .map_err(|e| Error::SeqRepoFaiQuery(e.to_string()))
+    }
+
+    /// Load many sequence fragments, given as `seq_id` and range, at once.
+    ///
+    /// Requests are grouped by file and sequence and sorted by offset.  Ranges that
+    /// overlap or are close to each other are read in one pass, so each BGZF block is
+    /// decompressed as few times as possible.  Results are returned in input order,
+    /// ranges are checked as in `fetch_sequence_part()`.
+    pub fn fetch_sequence_parts<S>(
+        &self,
+        requests: &[(S, Range<usize>)],
+    ) -> Result<Vec<String>, Error>
+    where
+        S: AsRef<str>,
+    {
+        // Look up the sequence information once per sequence.
+        let mut seqinfos: HashMap<&str, SeqInfoRecord> = HashMap::new();
+        for (seq_id, _) in requests {
+            let seq_id = seq_id.as_ref();
+            if !seqinfos.contains_key(seq_id) {
+                seqinfos.insert(seq_id, self.fetch_seqinfo(seq_id)?);
+            }
+        }
+
+        let ranges = requests
+            .iter()
+            .map(|(seq_id, range)| {
+                self.check_range(
+                    &seqinfos[seq_id.as_ref()],
+                    Some(range.start),
+                    Some(range.end),
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Group the requests by file and sequence.
+        let mut groups: BTreeMap<(&str, &str), Vec<usize>> = BTreeMap::new();
+        for (i, (seq_id, _)) in requests.iter().enumerate() {
+            let seq_id = seq_id.as_ref();
+            groups
+                .entry((seqinfos[seq_id].relpath.as_str(), seq_id))
+                .or_default()
+                .push(i);
+        }
+
+        let mut results = vec![String::new(); requests.len()];
+        for ((relpath, seq_id), mut indices) in groups {
+            indices.sort_by_key(|&i| ranges[i].start);
+
+            let reader = self.reader(relpath)?;
+            let mut fai_reader = reader.lock().map_err(|_| Error::MutexFastaReader)?;
+            let fai_layout = find_fai_layout(fai_reader.index(), seq_id)?;
+
+            // Merge close ranges into spans and read each span at once.
+            for (span, members) in merge_ranges(&ranges, &indices, self.config.max_merged_span) {
+                let Range {
+                    start: span_start,
+                    end: span_end,
+                } = span;
+                let mut buf = Vec::with_capacity(span_end - span_start);
+                SequenceReader::new(
+                    fai_reader.get_mut(),
+                    &fai_layout,
+                    span_start as u64,
+                    span_end as u64,
+                )
+                .and_then(|mut reader| reader.read_to_end(&mut buf))
+                .map_err(|e| Error::SeqRepoFaiQuery(e.to_string()))?;
+
+                for &i in &indices[members] {
+                    let range = &ranges[i];
+                    let bases = &buf[(range.start - span_start)..(range.end - span_start)];
+                    results[i] = String::from_utf8(bases.to_vec())
+                        .map_err(|e| Error::SeqRepoFastaRead(e.to_string()))?;
+                }
+            }
+        }
+
+        Ok(results)
+    }
+
+    /// Open a streaming reader over the range `begin..end` of the sequence `seq_id`.
+    ///
+    /// The reader uses its own handle on the BGZF file, so it does not block other
+    /// fetches and only holds one BGZF block in memory at a time.
+    pub fn sequence_reader(
+        &self,
+        seq_id: &str,
+        begin: Option<usize>,
+        end: Option<usize>,
+    ) -> Result<SequenceReader, Error> {
         let seqinfo = self.fetch_seqinfo(seq_id)?;
+        let Range { start, end } = self.check_range(&seqinfo, begin, end)?;
+
+        let fai_layout = {
+            let reader = self.reader(&seqinfo.relpath)?;
+            let fai_reader = reader.lock().map_err(|_| Error::MutexFastaReader)?;
+            find_fai_layout(fai_reader.index(), seq_id)?
+        };
+        let bgzf_reader = self.open_bgzf_reader(&seqinfo.relpath)?;
+
+        SequenceReader::new(bgzf_reader, &fai_layout, start as u64, end as u64)
+            .map_err(|e| Error::SeqRepoFaiQuery(e.to_string()))
+    }
+
+    /// Check the range `begin..end` of the sequence described by `seqinfo`.
+    ///
+    /// Missing ends default to the start and end of the sequence.  Invalid ranges
+    /// are rejected or, in lenient mode, clamped to the sequence.
+    fn check_range(
+        &self,
+        seqinfo: &SeqInfoRecord,
+        begin: Option<usize>,
+        end: Option<usize>,
+    ) -> Result<Range<usize>, Error> {
+        let len = seqinfo.len;
+        let begin = begin.unwrap_or(0);
+        // Without `end`, a `begin` after the end of the sequence is out of bounds.
+        let end = end.unwrap_or(std::cmp::max(begin, len));
+        if self.config.lenient_ranges {
+            let end = std::cmp::min(end, len);
+            Ok(std::cmp::min(begin, end)..end)
+        } else if begin > end {
+            Err(Error::InvalidRange(seqinfo.seq_id.clone(), begin, end))
+        } else if end > len {
+            Err(Error::RangeOutOfBounds(
+                seqinfo.seq_id.clone(),
+                begin,
+                end,
+                len,
+            ))
+        } else {
+            Ok(begin..end)
+        }
+    }
+
+    /// Obtain indexed reader for `relpath` from the pool, opening it if necessary.
+    fn reader(&self, relpath: &str) -> Result<Arc<Mutex<FastaReader>>, Error> {
+        if let Some(reader) = self
+            .readers
+            .lock()
+            .map_err(|_| Error::MutexFastaReader)?
+            .get(relpath)
+        {
+            return Ok(reader);
+        }
+
+        // Open the reader without holding the lock on the pool.
+        let reader = self.open_reader(relpath)?;
+        Ok(self
+            .readers
+            .lock()
+            .map_err(|_| Error::MutexFastaReader)?
+            .insert(relpath, reader))
+    }
 
-        let path_bgzip = self.root_dir.join(seqinfo.relpath);
+    /// Open indexed reader for the BGZF compressed file at `relpath`.
+    fn open_reader(&self, relpath: &str) -> Result<FastaReader, Error> {
+        let path_bgzip = self.root_dir.join(relpath);
         let path_bgzip = path_bgzip.as_path().to_str().unwrap();
 
-        let bgzf_index = noodles::bgzf::gzi::read(format!("{path_bgzip}.gzi"))
-            .map_err(|e| Error::SeqRepoGziOpen(e.to_string()))?;
-        let bgzf_reader = noodles::bgzf::indexed_reader::Builder::default()
-            .set_index(bgzf_index)
-            .build_from_path(path_bgzip)
-            .map_err(|e| Error::SeqRepoBgzfOpen(e.to_string()))?;
+        let bgzf_reader = self.open_bgzf_reader(relpath)?;
         let fai_index = noodles::fasta::fai::read(format!("{path_bgzip}.fai"))
             .map_err(|e| Error::SeqRepoFaiOpen(e.to_string()))?;
-        let mut fai_reader = noodles::fasta::indexed_reader::Builder::default()
+        noodles::fasta::indexed_reader::Builder::default()
             .set_index(fai_index)
             .build_from_reader(bgzf_reader)
-            .map_err(|e| Error::SeqRepoFastaOpen(e.to_string()))?;
-
-        let start = Position::try_from(begin.map(|start| start + 1).unwrap_or(1))
-            .map_err(|e| Error::ConvertPosition(e.to_string()))?;
-        let end = Position::try_from(
-            end.map(|end| std::cmp::min(end, seqinfo.len))
-                .unwrap_or(seqinfo.len),
-        )
-        .map_err(|e| Error::ConvertPosition(e.to_string()))?;
-        let region = Region::new(seq_id, start..=end);
+            .map_err(|e| Error::SeqRepoFastaOpen(e.to_string()))
+    }
 
-        let record = fai_reader
-            .query(&region)
-            .map_err(|e| Error::SeqRepoFaiQuery(e.to_string()))?;
+    /// Open indexed BGZF reader for the file at `relpath`.
+    fn open_bgzf_reader(&self, relpath: &str) -> Result<noodles::bgzf::IndexedReader<File>, Error> {
+        let path_bgzip = self.root_dir.join(relpath);
+        let path_bgzip = path_bgzip.as_path().to_str().unwrap();
 
-        Ok(std::str::from_utf8(record.sequence().as_ref())
-            .unwrap()
-            .to_string())
+        let bgzf_index = noodles::bgzf::gzi::read(format!("{path_bgzip}.gzi"))
+            .map_err(|e| Error::SeqRepoGziOpen(e.to_string()))?;
+        noodles::bgzf::indexed_reader::Builder::default()
+            .set_index(bgzf_index)
+            .build_from_path(path_bgzip)
+            .map_err(|e| Error::SeqRepoBgzfOpen(e.to_string()))
     }
 }
 
 #[cfg(test)]
 mod test {
-    use super::FastaDir;
+    use super::{merge_ranges, EvictionPolicy, FastaDir, FastaDirConfig, ReaderPool};
+    use std::io::Read;
+    use std::ops::Range;
 
     use anyhow::Error;
     use pretty_assertions::assert_eq;
+    use temp_testdir::TempDir;
 
     #[test]
     fn test_sync() {
@@ -200,6 +968,10 @@ mod test {
             alpha: \"ACGT\", added: 2023-02-16T09:46:06, \
             relpath: \"2023/0216/0946/1676540766.9148078.fa.bgz\" }",
         );
+        assert!(matches!(
+            fd.fetch_seqinfo("xyz"),
+            Err(crate::Error::UnknownSeqId(seq_id)) if seq_id == "xyz"
+        ));
 
         Ok(())
     }
@@ -261,6 +1033,339 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    fn fetch_sequence_part_into() -> Result<(), Error> {
+        let fd = FastaDir::new("tests/data/seqrepo/latest/sequences")?;
+        let seq_id = "5q5HZTCRudL17NTiv5Bn6th__0FrZH04";
+        let seq = fd.fetch_sequence(seq_id)?;
+
+        // Ranges within lines, across line ends and up to the end of the sequence.
+        let mut buf = Vec::new();
+        for (begin, end) in [
+            (0, 10),
+            (55, 65),
+            (95, 205),
+            (0, seq.len()),
+            (seq.len(), seq.len()),
+        ] {
+            buf.clear();
+            fd.fetch_sequence_part_into(seq_id, Some(begin), Some(end), &mut buf)?;
+            assert_eq!(buf, &seq.as_bytes()[begin..end], "{begin}..{end}");
+        }
+
+        // Appends to the buffer.
+        let mut buf = b"ACGT".to_vec();
+        fd.fetch_sequence_part_into(seq_id, Some(seq.len() - 5), None, &mut buf)?;
+        assert_eq!(buf, format!("ACGT{}", &seq[seq.len() - 5..]).as_bytes());
+
+        Ok(())
+    }
+
+    #[test]
+    fn fetch_sequence_part_out_of_range() -> Result<(), Error> {
+        let fd = FastaDir::new("tests/data/seqrepo/latest/sequences")?;
+        let seq_id = "5q5HZTCRudL17NTiv5Bn6th__0FrZH04";
+
+        assert_eq!(
+            fd.fetch_sequence_part(seq_id, Some(1870), Some(1880))
+                .unwrap_err()
+                .to_string(),
+            "range 1870..1880 out of bounds for sequence \
+            5q5HZTCRudL17NTiv5Bn6th__0FrZH04 of length 1873"
+        );
+        assert_eq!(
+            fd.fetch_sequence_part(seq_id, Some(2000), None)
+                .unwrap_err()
+                .to_string(),
+            "range 2000..2000 out of bounds for sequence \
+            5q5HZTCRudL17NTiv5Bn6th__0FrZH04 of length 1873"
+        );
+        assert!(matches!(
+            fd.fetch_sequence_part(seq_id, Some(1874), None),
+            Err(crate::Error::RangeOutOfBounds(_, 1874, 1874, 1873))
+        ));
+        assert!(matches!(
+            fd.fetch_sequence_part(seq_id, Some(10), Some(5)),
+            Err(crate::Error::InvalidRange(_, 10, 5))
+        ));
+        assert!(fd.sequence_reader(seq_id, Some(0), Some(1874)).is_err());
+        assert!(fd
+            .fetch_sequence_parts(&[(seq_id, 0..10), (seq_id, 1870..1880)])
+            .is_err());
+        assert_eq!(fd.fetch_sequence_part(seq_id, Some(1873), None)?, "");
+
+        Ok(())
+    }
+
+    #[test]
+    fn fetch_sequence_part_lenient() -> Result<(), Error> {
+        let fd = FastaDir::new_with_config(
+            "tests/data/seqrepo/latest/sequences",
+            FastaDirConfig {
+                lenient_ranges: true,
+                ..Default::default()
+            },
+        )?;
+        let seq_id = "5q5HZTCRudL17NTiv5Bn6th__0FrZH04";
+
+        assert_eq!(
+            fd.fetch_sequence_part(seq_id, Some(1869), Some(1880))?,
+            "TATA"
+        );
+        assert_eq!(fd.fetch_sequence_part(seq_id, Some(2000), None)?, "");
+        assert_eq!(fd.fetch_sequence_part(seq_id, Some(10), Some(5))?, "");
+        assert_eq!(
+            fd.sequence_reader(seq_id, Some(1869), Some(1880))?
+                .remaining(),
+            4
+        );
+        assert_eq!(
+            fd.fetch_sequence_parts(&[
+                (seq_id, 1869..1880),
+                (seq_id, Range { start: 10, end: 5 })
+            ])?,
+            vec!["TATA", ""]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn sequence_reader() -> Result<(), Error> {
+        let fd = FastaDir::new("tests/data/seqrepo/latest/sequences")?;
+        let seq_id = "5q5HZTCRudL17NTiv5Bn6th__0FrZH04";
+        let seq = fd.fetch_sequence(seq_id)?;
+
+        let mut reader = fd.sequence_reader(seq_id, Some(95), Some(205))?;
+        assert_eq!(reader.remaining(), 110);
+        // Read in small chunks, crossing line ends.
+        let mut chunk = [0u8; 7];
+        let mut result = Vec::new();
+        loop {
+            let n = reader.read(&mut chunk)?;
+            if n == 0 {
+                break;
+            }
+            result.extend_from_slice(&chunk[..n]);
+        }
+        assert_eq!(result, &seq.as_bytes()[95..205]);
+        assert_eq!(reader.remaining(), 0);
+
+        let mut result = String::new();
+        fd.sequence_reader(seq_id, None, None)?
+            .read_to_string(&mut result)?;
+        assert_eq!(result, seq);
+
+        Ok(())
+    }
+
+    #[test]
+    fn fetch_sequence_parts() -> Result<(), Error> {
+        let fd = FastaDir::new("tests/data/seqrepo/latest/sequences")?;
+        let seq_id = "5q5HZTCRudL17NTiv5Bn6th__0FrZH04";
+        let seq = fd.fetch_sequence(seq_id)?;
+
+        // Unordered, overlapping and empty ranges.
+        let ranges = [
+            500..510,
+            0..10,
+            5..15,
+            3..3,
+            95..205,
+            seq.len() - 5..seq.len(),
+        ];
+        let requests: Vec<_> = ranges.iter().map(|range| (seq_id, range.clone())).collect();
+        let expected: Vec<_> = ranges
+            .iter()
+            .map(|range| seq[range.clone()].to_string())
+            .collect();
+
+        assert_eq!(fd.fetch_sequence_parts(&requests)?, expected);
+        assert!(fd.fetch_sequence_parts(&[("unknown", 0..10)]).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn merge_ranges_capped() {
+        // Windows closer than `MERGE_GAP` to each other, spanning 100 Mbp in total.
+        let ranges: Vec<_> = (0..10_000)
+            .map(|i| (i * 10_000)..(i * 10_000 + 100))
+            .collect();
+        let indices: Vec<_> = (0..ranges.len()).collect();
+
+        let spans = merge_ranges(&ranges, &indices, 1 << 20);
+        assert_eq!(spans.len(), 96);
+        let mut next = 0;
+        for (span, members) in spans {
+            assert!(span.len() <= 1 << 20);
+            assert_eq!(members.start, next);
+            assert_eq!(span.start, ranges[members.start].start);
+            assert_eq!(span.end, ranges[members.end - 1].end);
+            next = members.end;
+        }
+        assert_eq!(next, ranges.len());
+
+        // Single ranges longer than the limit are kept as they are.
+        assert_eq!(
+            merge_ranges(&[0..100, 50..60], &[0, 1], 10),
+            vec![(0..100, 0..1), (50..60, 1..2)]
+        );
+    }
+
+    #[test]
+    fn fetch_sequence_parts_capped() -> Result<(), Error> {
+        let fd = FastaDir::new_with_config(
+            "tests/data/seqrepo/latest/sequences",
+            FastaDirConfig {
+                max_merged_span: 25,
+                ..Default::default()
+            },
+        )?;
+        let seq_id = "5q5HZTCRudL17NTiv5Bn6th__0FrZH04";
+        let seq = fd.fetch_sequence(seq_id)?;
+
+        let requests: Vec<_> = (0..150)
+            .map(|i| (seq_id, (i * 12)..(i * 12 + 10)))
+            .collect();
+        let expected: Vec<_> = requests
+            .iter()
+            .map(|(_, range)| seq[range.clone()].to_string())
+            .collect();
+        assert_eq!(fd.fetch_sequence_parts(&requests)?, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn fetch_sequence_part_pooled() -> Result<(), Error> {
+        let fd = FastaDir::new("tests/data/seqrepo/latest/sequences")?;
+        let seq_id = "5q5HZTCRudL17NTiv5Bn6th__0FrZH04";
+
+        assert_eq!(fd.readers.lock().unwrap().entries.len(), 0);
+        assert_eq!(
+            fd.fetch_sequence_part(seq_id, Some(0), Some(10))?,
+            "ACTGCTGAGC"
+        );
+        assert_eq!(fd.readers.lock().unwrap().entries.len(), 1);
+        assert_eq!(
+            fd.fetch_sequence_part(seq_id, Some(100), Some(110))?,
+            "ATGTAGGTAA"
+        );
+        assert_eq!(fd.readers.lock().unwrap().entries.len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn fetch_sequence_part_unpooled() -> Result<(), Error> {
+        let fd = FastaDir::new_with_config(
+            "tests/data/seqrepo/latest/sequences",
+            FastaDirConfig {
+                reader_pool_capacity: 0,
+                ..Default::default()
+            },
+        )?;
+        let seq_id = "5q5HZTCRudL17NTiv5Bn6th__0FrZH04";
+
+        assert_eq!(
+            fd.fetch_sequence_part(seq_id, Some(0), Some(10))?,
+            "ACTGCTGAGC"
+        );
+        assert_eq!(fd.readers.lock().unwrap().entries.len(), 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn store_and_commit() -> Result<(), Error> {
+        let temp = TempDir::default();
+        let fd = FastaDir::init(temp.as_ref().join("sequences"))?;
+
+        // Use a sequence spanning multiple BGZF blocks.
+        let seq = "ACGTTGCAAC".repeat(20_000);
+        assert!(fd.store("seq", &seq)?);
+        assert!(!fd.store("seq", &seq)?);
+        assert!(!fd.contains("seq")?);
+        fd.commit()?;
+        assert!(fd.contains("seq")?);
+
+        let seqinfo = fd.fetch_seqinfo("seq")?;
+        assert_eq!(seqinfo.len, 200_000);
+        assert_eq!(seqinfo.alpha, "ACGT");
+        let gzi = std::fs::read(
+            temp.as_ref()
+                .join("sequences")
+                .join(format!("{}.gzi", &seqinfo.relpath)),
+        )?;
+        assert!(u64::from_le_bytes(gzi[..8].try_into()?) > 0);
+
+        assert_eq!(fd.fetch_sequence("seq")?, seq);
+        assert_eq!(
+            fd.fetch_sequence_part("seq", Some(199_990), None)?,
+            "ACGTTGCAAC"
+        );
+        // Range crossing the BGZF block boundary.
+        assert_eq!(
+            fd.fetch_sequence_part("seq", Some(64_000), Some(67_000))?,
+            &seq[64_000..67_000]
+        );
+        assert_eq!(
+            fd.fetch_sequence_parts(&[("seq", 150_000..150_010), ("seq", 10..20)])?,
+            vec![&seq[150_000..150_010], &seq[10..20]]
+        );
+        let mut streamed = String::new();
+        fd.sequence_reader("seq", Some(64_000), Some(67_000))?
+            .read_to_string(&mut streamed)?;
+        assert_eq!(streamed, &seq[64_000..67_000]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn store_read_only() -> Result<(), Error> {
+        let fd = FastaDir::new("tests/data/seqrepo/latest/sequences")?;
+        assert!(fd.store("seq", "ACGT").is_err());
+
+        Ok(())
+    }
+
+    fn pool_keys<T>(pool: &ReaderPool<T>) -> Vec<String> {
+        pool.entries.iter().map(|(k, _)| k.clone()).collect()
+    }
+
+    #[test]
+    fn reader_pool_lru() {
+        let mut pool = ReaderPool::new(2, EvictionPolicy::LeastRecentlyUsed);
+        pool.insert("a", 1);
+        pool.insert("b", 2);
+        assert_eq!(*pool.get("a").unwrap().lock().unwrap(), 1);
+        pool.insert("c", 3);
+
+        assert_eq!(pool_keys(&pool), vec!["a", "c"]);
+        assert!(pool.get("b").is_none());
+    }
+
+    #[test]
+    fn reader_pool_fifo() {
+        let mut pool = ReaderPool::new(2, EvictionPolicy::FirstInFirstOut);
+        pool.insert("a", 1);
+        pool.insert("b", 2);
+        assert_eq!(*pool.get("a").unwrap().lock().unwrap(), 1);
+        pool.insert("c", 3);
+
+        assert_eq!(pool_keys(&pool), vec!["b", "c"]);
+        assert!(pool.get("a").is_none());
+    }
+
+    #[test]
+    fn reader_pool_insert_existing() {
+        let mut pool = ReaderPool::new(2, EvictionPolicy::default());
+        pool.insert("a", 1);
+        assert_eq!(*pool.insert("a", 2).lock().unwrap(), 1);
+        assert_eq!(pool.entries.len(), 1);
+    }
 }
 
 // <LICENSE>
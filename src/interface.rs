@@ -1,6 +1,16 @@
 //! Implementation of the interface trait.
 
+use std::collections::BTreeSet;
+use std::fmt::Display;
+use std::ops::Range;
+use std::str::FromStr;
+
+use chrono::NaiveDateTime;
+
+use crate::aliases::{seq_id_from_alias, split_identifier, GA4GH_PREFIX};
+use crate::digest::{self, SequenceDigests};
 use crate::error::Error;
+use crate::{translate, Namespace, NamespacedAlias, Region, Strand, TranslateOptions};
 
 /// Trait describing the interface of a sequence repository.
 pub trait Interface {
@@ -16,8 +26,163 @@ pub trait Interface {
         begin: Option<usize>,
         end: Option<usize>,
     ) -> Result<String, Error>;
+
+    /// Fetch the sequence of the given region.
+    fn fetch_region(&self, region: &Region) -> Result<String, Error> {
+        self.fetch_sequence_part(&region.alias_or_seq_id, region.begin, region.end)
+    }
+
+    /// Fetch part sequence given an alias on the given strand.
+    ///
+    /// `begin` and `end` refer to the stored (forward) sequence, for `Strand::Reverse`
+    /// the reverse complement of this part is returned.
+    fn fetch_sequence_part_stranded(
+        &self,
+        alias_or_seq_id: &AliasOrSeqId,
+        begin: Option<usize>,
+        end: Option<usize>,
+        strand: Strand,
+    ) -> Result<String, Error> {
+        Ok(strand.apply(self.fetch_sequence_part(alias_or_seq_id, begin, end)?))
+    }
+
+    /// Fetch part sequence given an alias and translate it to a protein sequence.
+    ///
+    /// For the minus strand, translate the result of `fetch_sequence_part_stranded()`
+    /// with `translate()`.
+    fn translate_sequence_part(
+        &self,
+        alias_or_seq_id: &AliasOrSeqId,
+        begin: Option<usize>,
+        end: Option<usize>,
+        options: &TranslateOptions,
+    ) -> Result<String, Error> {
+        translate(
+            &self.fetch_sequence_part(alias_or_seq_id, begin, end)?,
+            options,
+        )
+    }
+
+    /// Fetch part sequence given an alias, appending its bytes to `buf`.
+    ///
+    /// This allows for reusing buffers.  The default implementation copies the result
+    /// of `fetch_sequence_part()`, implementations may read directly into `buf`.
+    fn fetch_sequence_part_into(
+        &self,
+        alias_or_seq_id: &AliasOrSeqId,
+        begin: Option<usize>,
+        end: Option<usize>,
+        buf: &mut Vec<u8>,
+    ) -> Result<(), Error> {
+        let seq = self.fetch_sequence_part(alias_or_seq_id, begin, end)?;
+        buf.extend_from_slice(seq.as_bytes());
+        Ok(())
+    }
+
+    /// Fetch many sequence parts given as alias and range at once.
+    ///
+    /// Results are returned in input order.  The default implementation calls
+    /// `fetch_sequence_part()` for each request, implementations may resolve aliases
+    /// once and batch the reads.
+    fn fetch_sequence_parts(
+        &self,
+        requests: &[(AliasOrSeqId, Range<usize>)],
+    ) -> Result<Vec<String>, Error> {
+        requests
+            .iter()
+            .map(|(alias_or_seq_id, range)| {
+                self.fetch_sequence_part(alias_or_seq_id, Some(range.start), Some(range.end))
+            })
+            .collect()
+    }
+
+    /// Fetch the metadata of the sequence given an alias.
+    ///
+    /// The default implementation fetches the whole sequence and describes it with
+    /// `SequenceInfo::from_sequence()`, implementations should provide the stored
+    /// metadata instead.
+    fn sequence_info(&self, alias_or_seq_id: &AliasOrSeqId) -> Result<SequenceInfo, Error> {
+        SequenceInfo::from_sequence(&self.fetch_sequence(alias_or_seq_id)?)
+    }
+
+    /// Compute the `seq_id` that `seq` has (or would have) in the repository.
+    fn seq_id_for(&self, seq: &str) -> Result<String, Error> {
+        digest::seq_sha512t24u(seq)
+    }
+
+    /// Return the `seq_id` of `seq` if the repository contains the sequence.
+    fn find_sequence(&self, seq: &str) -> Result<Option<String>, Error> {
+        let seq_id = self.seq_id_for(seq)?;
+        match self.fetch_sequence(&AliasOrSeqId::SeqId(seq_id.clone())) {
+            Ok(_) => Ok(Some(seq_id)),
+            Err(e) if e.is_not_found() => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Return whether the repository contains the sequence `seq`.
+    fn contains_sequence(&self, seq: &str) -> Result<bool, Error> {
+        Ok(self.find_sequence(seq)?.is_some())
+    }
 }
 
+/// Metadata of a sequence as returned by `Interface::sequence_info()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SequenceInfo {
+    /// The `seq_id` of the sequence.
+    pub seq_id: String,
+    /// Length of the sequence.
+    pub len: usize,
+    /// Alphabet of the sequence, i.e., its sorted distinct characters, if known.
+    pub alphabet: Option<String>,
+    /// Time that the sequence was added to the repository (UTC), if known.
+    pub added: Option<NaiveDateTime>,
+    /// Current aliases of the sequence.
+    ///
+    /// Namespaces are given as stored in the database, e.g., `NCBI:NM_000551.3`, unlike
+    /// the canonical form of identifiers such as `refseq:NM_000551.3`, see
+    /// `Namespace::canonical_name()`.
+    pub aliases: Vec<NamespacedAlias>,
+}
+
+impl SequenceInfo {
+    /// Describe the sequence `seq` without a repository.
+    ///
+    /// The `seq_id` and aliases are computed from the digests of the sequence as for
+    /// sequences added to a repository, `added` is unknown.
+    pub fn from_sequence(seq: &str) -> Result<Self, Error> {
+        let seq = digest::normalize_sequence(seq)?;
+        let digests = SequenceDigests::from_normalized(&seq);
+        let aliases = [
+            (Namespace::MD5, digests.md5.clone()),
+            (Namespace::SEGUID, digests.seguid.clone()),
+            (Namespace::SHA1, digests.sha1.clone()),
+            (Namespace::VMC, digests.vmc_alias()),
+        ]
+        .into_iter()
+        .map(|(namespace, alias)| NamespacedAlias {
+            namespace: Namespace::new(namespace),
+            alias,
+        })
+        .collect();
+
+        Ok(Self {
+            seq_id: digests.sha512t24u,
+            len: seq.len(),
+            alphabet: Some(seq.chars().collect::<BTreeSet<_>>().into_iter().collect()),
+            added: None,
+            aliases,
+        })
+    }
+}
+
+/// Identification of a sequence, either by alias or by `seq_id`.
+///
+/// Can be parsed from identifiers such as `NC_000001.11`, `refseq:NM_000551.3`,
+/// `md5:<digest>`, `ga4gh:SQ.<seq_id>`, or `sha512t24u:<seq_id>`.  Namespace
+/// synonyms are mapped to the values stored in the database, see
+/// `Namespace::normalize()`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum AliasOrSeqId {
     Alias {
         value: String,
@@ -25,3 +190,160 @@ pub enum AliasOrSeqId {
     },
     SeqId(String),
 }
+
+impl FromStr for AliasOrSeqId {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (namespace, alias) = split_identifier(s.trim());
+        if alias.is_empty() || namespace.is_some_and(str::is_empty) {
+            return Err(Error::InvalidIdentifier(s.to_string()));
+        }
+
+        match namespace.map(Namespace::normalize) {
+            Some(namespace) if namespace.is_seq_id() => seq_id_from_alias(&namespace, alias)
+                .filter(|seq_id| !seq_id.is_empty())
+                .map(|seq_id| AliasOrSeqId::SeqId(seq_id.to_string()))
+                .ok_or_else(|| Error::InvalidIdentifier(s.to_string())),
+            namespace => Ok(AliasOrSeqId::Alias {
+                value: alias.to_string(),
+                namespace: namespace.map(|namespace| namespace.value),
+            }),
+        }
+    }
+}
+
+/// Formats in canonical form, e.g., `refseq:NM_000551.3` or `ga4gh:SQ.<seq_id>`.
+impl Display for AliasOrSeqId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AliasOrSeqId::Alias {
+                value,
+                namespace: Some(namespace),
+            } => write!(
+                f,
+                "{}:{}",
+                Namespace::normalize(namespace).canonical_name(),
+                value
+            ),
+            AliasOrSeqId::Alias {
+                value,
+                namespace: None,
+            } => write!(f, "{value}"),
+            AliasOrSeqId::SeqId(seq_id) => {
+                write!(f, "{}:{}{}", Namespace::GA4GH, GA4GH_PREFIX, seq_id)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::{AliasOrSeqId, Interface};
+    use crate::{Error, SeqRepo};
+
+    /// Implementation providing only the required methods of `Interface`.
+    struct FetchOnly(SeqRepo);
+
+    impl Interface for FetchOnly {
+        fn fetch_sequence_part(
+            &self,
+            alias_or_seq_id: &AliasOrSeqId,
+            begin: Option<usize>,
+            end: Option<usize>,
+        ) -> Result<String, Error> {
+            self.0.fetch_sequence_part(alias_or_seq_id, begin, end)
+        }
+    }
+
+    fn alias(value: &str, namespace: Option<&str>) -> AliasOrSeqId {
+        AliasOrSeqId::Alias {
+            value: value.to_string(),
+            namespace: namespace.map(|s| s.to_string()),
+        }
+    }
+
+    #[test]
+    fn parse() -> Result<(), anyhow::Error> {
+        assert_eq!(
+            "NC_000001.11".parse::<AliasOrSeqId>()?,
+            alias("NC_000001.11", None)
+        );
+        assert_eq!(
+            "refseq:NM_000551.3".parse::<AliasOrSeqId>()?,
+            alias("NM_000551.3", Some("NCBI"))
+        );
+        assert_eq!(
+            "md5:a8e7e4cbd2fa521b45b23692b2dd601c".parse::<AliasOrSeqId>()?,
+            alias("a8e7e4cbd2fa521b45b23692b2dd601c", Some("MD5"))
+        );
+        assert_eq!(
+            "GRCh38:1".parse::<AliasOrSeqId>()?,
+            alias("1", Some("GRCh38"))
+        );
+        assert_eq!(
+            "ga4gh:SQ.5q5HZTCRudL17NTiv5Bn6th__0FrZH04".parse::<AliasOrSeqId>()?,
+            AliasOrSeqId::SeqId("5q5HZTCRudL17NTiv5Bn6th__0FrZH04".to_string())
+        );
+        assert_eq!(
+            "sha512t24u:5q5HZTCRudL17NTiv5Bn6th__0FrZH04".parse::<AliasOrSeqId>()?,
+            AliasOrSeqId::SeqId("5q5HZTCRudL17NTiv5Bn6th__0FrZH04".to_string())
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn sequence_info_default() -> Result<(), anyhow::Error> {
+        let repo = FetchOnly(SeqRepo::new("tests/data/seqrepo", "latest")?);
+        let aos = "refseq:NM_001304430.2".parse()?;
+
+        let info = repo.sequence_info(&aos)?;
+        let expected = repo.0.sequence_info(&aos)?;
+        assert_eq!(info.seq_id, expected.seq_id);
+        assert_eq!(info.len, expected.len);
+        assert_eq!(info.alphabet, expected.alphabet);
+        assert_eq!(info.added, None);
+        assert_eq!(
+            info.aliases
+                .iter()
+                .map(|alias| alias.to_string())
+                .collect::<Vec<_>>(),
+            vec![
+                "MD5:a8e7e4cbd2fa521b45b23692b2dd601c",
+                "SEGUID:U5AvKXlRSRwJgn/Zxsa286iO/sg",
+                "SHA1:53902f297951491c09827fd9c6c6b6f3a88efec8",
+                "VMC:GS_5q5HZTCRudL17NTiv5Bn6th__0FrZH04",
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_invalid() {
+        for s in ["", "refseq:", ":NM_000551.3", "ga4gh:xyz", "ga4gh:SQ."] {
+            assert!(s.parse::<AliasOrSeqId>().is_err(), "{s:?}");
+        }
+    }
+
+    #[test]
+    fn display() -> Result<(), anyhow::Error> {
+        for (input, expected) in [
+            ("NC_000001.11", "NC_000001.11"),
+            ("NCBI:NM_000551.3", "refseq:NM_000551.3"),
+            ("RefSeq:NM_000551.3", "refseq:NM_000551.3"),
+            (
+                "md5:a8e7e4cbd2fa521b45b23692b2dd601c",
+                "MD5:a8e7e4cbd2fa521b45b23692b2dd601c",
+            ),
+            ("sha512t24u:xyz", "ga4gh:SQ.xyz"),
+        ] {
+            assert_eq!(input.parse::<AliasOrSeqId>()?.to_string(), expected);
+        }
+
+        Ok(())
+    }
+}
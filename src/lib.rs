@@ -2,23 +2,59 @@
 
 #[cfg(feature = "impl")]
 pub(crate) mod aliases;
+#[cfg(feature = "async")]
+pub(crate) mod async_interface;
 #[cfg(feature = "cached")]
 pub(crate) mod cached;
+#[cfg(feature = "impl")]
+pub mod digest;
 pub(crate) mod error;
 #[cfg(feature = "impl")]
+pub(crate) mod fallback;
+#[cfg(feature = "impl")]
 pub(crate) mod fasta;
 #[cfg(feature = "impl")]
 pub(crate) mod interface;
 #[cfg(feature = "impl")]
+pub(crate) mod overlay;
+#[cfg(feature = "impl")]
+pub(crate) mod pool;
+#[cfg(feature = "refget-client")]
+pub(crate) mod refget;
+#[cfg(feature = "impl")]
+pub(crate) mod region;
+#[cfg(feature = "impl")]
 pub(crate) mod repo;
+#[cfg(feature = "impl")]
+pub(crate) mod schema;
+#[cfg(feature = "server")]
+pub mod server;
+#[cfg(feature = "impl")]
+pub(crate) mod strand;
+#[cfg(feature = "impl")]
+pub(crate) mod translate;
 
 pub use crate::aliases::*;
+#[cfg(feature = "async")]
+pub use crate::async_interface::*;
 #[cfg(feature = "cached")]
 pub use crate::cached::*;
 pub use crate::error::*;
 #[cfg(feature = "impl")]
+pub use crate::fallback::*;
+#[cfg(feature = "impl")]
 pub use crate::fasta::*;
 #[cfg(feature = "impl")]
 pub use crate::interface::*;
 #[cfg(feature = "impl")]
+pub use crate::overlay::*;
+#[cfg(feature = "refget-client")]
+pub use crate::refget::*;
+#[cfg(feature = "impl")]
+pub use crate::region::*;
+#[cfg(feature = "impl")]
 pub use crate::repo::*;
+#[cfg(feature = "impl")]
+pub use crate::strand::*;
+#[cfg(feature = "impl")]
+pub use crate::translate::*;
@@ -0,0 +1,646 @@
+//! Command line interface to the `seqrepo` crate.
+
+use std::io::Write;
+
+use clap::{Args, Parser, Subcommand};
+use clap_verbosity_flag::{InfoLevel, Verbosity};
+use textwrap::wrap;
+use tracing::{debug, info};
+
+use seqrepo::{
+    self, AliasDbConfig, AliasDbRecord, AliasOrSeqId, CoordinateSystem, Error, FastaDirConfig,
+    Interface, Namespace, OrderBy, Query, Region, SeqRepo, SeqRepoConfig, Strand,
+};
+
+/// Commonly used command line arguments.
+#[derive(Parser, Debug)]
+pub struct CommonArgs {
+    /// Verbosity of the program
+    #[clap(flatten)]
+    pub verbose: Verbosity<InfoLevel>,
+
+    /// Root directory
+    #[arg(
+        short,
+        long,
+        env = "SEQREPO_ROOT_DIR",
+        default_value = "~/hgvs-rs-data/seqrepo-data"
+    )]
+    pub root_directory: String,
+}
+
+/// CLI parser based on clap.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    version,
+    about = "SeqRepo access written in Rust",
+    long_about = "Access to SeqRepo data from Rust: fetch and look up sequences, create and \
+        load instances, and serve them via the refget API"
+)]
+struct Cli {
+    /// Commonly used arguments
+    #[command(flatten)]
+    common: CommonArgs,
+
+    /// The sub command to run
+    #[command(subcommand)]
+    command: Commands,
+}
+
+/// Enum supporting the parsing of top-level commands.
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Subcommand)]
+enum Commands {
+    /// "export" sub command
+    Export(ExportArgs),
+    /// "fetch" sub command
+    Fetch(FetchArgs),
+    /// "info" sub command
+    Info(InfoArgs),
+    /// "init" sub command
+    Init(InitArgs),
+    /// "list-instances" sub command
+    ListInstances,
+    /// "load" sub command
+    Load(LoadArgs),
+    /// "search" sub command
+    Search(SearchArgs),
+    /// "serve" sub command
+    #[cfg(feature = "server")]
+    Serve(ServeArgs),
+    /// "translate" sub command
+    Translate(TranslateArgs),
+}
+
+/// Parsing of "export" subcommand
+#[derive(Debug, Args)]
+struct ExportArgs {
+    /// The namespace to use, e.g., "refseq" or "ensembl".
+    #[arg(short, long)]
+    pub namespace: Option<String>,
+    /// The instance name to use.
+    #[arg(short, long, default_value = "latest")]
+    pub instance_name: String,
+    /// The sequence aliases to query for.
+    #[arg()]
+    pub aliases: Vec<String>,
+}
+
+/// Implementation of "export" command.
+fn main_export(common_args: &CommonArgs, args: &ExportArgs) -> Result<(), Error> {
+    debug!("common_args = {:?}", &common_args);
+    debug!("args = {:?}", &args);
+
+    let seq_repo = SeqRepo::new(&common_args.root_directory, &args.instance_name)?;
+    let alias_db = seq_repo.alias_db();
+
+    let mut query = Query {
+        namespace: args.namespace.as_deref().map(Namespace::normalize),
+        ..Default::default()
+    };
+
+    let mut records: Vec<AliasDbRecord> = Vec::new();
+    if args.aliases.is_empty() {
+        records = alias_db.find_all(&query)?;
+    } else {
+        for alias in &args.aliases {
+            query.alias = Some(alias.clone());
+            records.extend(alias_db.find_all(&query)?);
+        }
+    }
+
+    for group in records.chunk_by_mut(|a, b| a.seqid == b.seqid) {
+        let seq = seq_repo.fetch_sequence(&seqrepo::AliasOrSeqId::SeqId(group[0].seqid.clone()))?;
+        group.sort_by(|a, b| a.namespace.value.cmp(&b.namespace.value));
+        let metas = group
+            .iter()
+            .map(|record| format!("{}:{}", *record.namespace, record.alias))
+            .collect::<Vec<_>>();
+
+        println!(">{}", metas.join(" "));
+        for line in wrap(&seq, 100) {
+            println!("{line}");
+        }
+    }
+
+    Ok(())
+}
+
+/// Parsing of "fetch" subcommand
+#[derive(Debug, Args)]
+struct FetchArgs {
+    /// The instance name to use.
+    #[arg(short, long, default_value = "latest")]
+    pub instance_name: String,
+    /// Interpret positions as 0-based half-open instead of 1-based inclusive.
+    #[arg(long)]
+    pub zero_based: bool,
+    /// Output the reverse complement.
+    #[arg(long)]
+    pub reverse: bool,
+    /// The regions to fetch, e.g., "NC_000001.11:1,000-2,000" or "refseq:NM_000551.3".
+    #[arg(required = true)]
+    pub regions: Vec<String>,
+}
+
+/// Map errors on writing to the output.
+fn output_error(e: std::io::Error) -> Error {
+    Error::WriteOutput(e.to_string())
+}
+
+/// Implementation of "fetch" command.
+fn main_fetch(
+    common_args: &CommonArgs,
+    args: &FetchArgs,
+    out: &mut impl Write,
+) -> Result<(), Error> {
+    debug!("common_args = {:?}", &common_args);
+    debug!("args = {:?}", &args);
+
+    let seq_repo = SeqRepo::new(&common_args.root_directory, &args.instance_name)?;
+    let coordinate_system = if args.zero_based {
+        CoordinateSystem::ZeroBasedHalfOpen
+    } else {
+        CoordinateSystem::OneBasedInclusive
+    };
+    let strand = if args.reverse {
+        Strand::Reverse
+    } else {
+        Strand::Forward
+    };
+
+    for region in &args.regions {
+        let region = Region::parse(region, coordinate_system)?;
+        let seq = seq_repo.fetch_sequence_part_stranded(
+            &region.alias_or_seq_id,
+            region.begin,
+            region.end,
+            strand,
+        )?;
+        writeln!(out, "{seq}").map_err(output_error)?;
+    }
+
+    Ok(())
+}
+
+/// Parsing of "info" subcommand
+#[derive(Debug, Args)]
+struct InfoArgs {
+    /// The instance name to use.
+    #[arg(short, long, default_value = "latest")]
+    pub instance_name: String,
+    /// The sequences to describe, e.g., "refseq:NM_000551.3" or "ga4gh:SQ.<seq_id>".
+    #[arg(required = true)]
+    pub identifiers: Vec<String>,
+}
+
+/// Implementation of "info" command.
+fn main_info(common_args: &CommonArgs, args: &InfoArgs, out: &mut impl Write) -> Result<(), Error> {
+    debug!("common_args = {:?}", &common_args);
+    debug!("args = {:?}", &args);
+
+    let seq_repo = SeqRepo::new(&common_args.root_directory, &args.instance_name)?;
+    for (i, identifier) in args.identifiers.iter().enumerate() {
+        let info = seq_repo.sequence_info(&identifier.parse::<AliasOrSeqId>()?)?;
+        if i > 0 {
+            writeln!(out).map_err(output_error)?;
+        }
+        writeln!(out, "identifier\t{identifier}").map_err(output_error)?;
+        writeln!(out, "seq_id\t{}", info.seq_id).map_err(output_error)?;
+        writeln!(out, "length\t{}", info.len).map_err(output_error)?;
+        if let Some(alphabet) = &info.alphabet {
+            writeln!(out, "alphabet\t{alphabet}").map_err(output_error)?;
+        }
+        if let Some(added) = &info.added {
+            writeln!(out, "added\t{added}").map_err(output_error)?;
+        }
+        for alias in &info.aliases {
+            writeln!(out, "alias\t{alias}").map_err(output_error)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Parsing of "init" subcommand
+#[derive(Debug, Args)]
+struct InitArgs {
+    /// The instance name to use.
+    #[arg(short, long, default_value = "latest")]
+    pub instance_name: String,
+}
+
+/// Implementation of "init" command.
+fn main_init(common_args: &CommonArgs, args: &InitArgs) -> Result<(), Error> {
+    debug!("common_args = {:?}", &common_args);
+    debug!("args = {:?}", &args);
+
+    SeqRepo::init(&common_args.root_directory, &args.instance_name)?;
+
+    Ok(())
+}
+
+/// Implementation of "list-instances" command.
+fn main_list_instances(common_args: &CommonArgs, out: &mut impl Write) -> Result<(), Error> {
+    debug!("common_args = {:?}", &common_args);
+
+    for instance in SeqRepo::list_instances(&common_args.root_directory)? {
+        match instance.symlink_target {
+            Some(target) => writeln!(out, "{} -> {}", instance.name, target.display()),
+            None => writeln!(out, "{}", instance.name),
+        }
+        .map_err(output_error)?;
+    }
+
+    Ok(())
+}
+
+/// Parsing of "load" subcommand
+#[derive(Debug, Args)]
+struct LoadArgs {
+    /// The namespace to register the sequence names in.
+    #[arg(short, long)]
+    pub namespace: String,
+    /// The instance name to use.
+    #[arg(short, long, default_value = "latest")]
+    pub instance_name: String,
+    /// The FASTA files to load.
+    #[arg(required = true)]
+    pub fasta_files: Vec<String>,
+}
+
+/// Implementation of "load" command.
+fn main_load(common_args: &CommonArgs, args: &LoadArgs) -> Result<(), Error> {
+    debug!("common_args = {:?}", &common_args);
+    debug!("args = {:?}", &args);
+
+    let seq_repo = SeqRepo::new_with_config(
+        &common_args.root_directory,
+        &args.instance_name,
+        SeqRepoConfig {
+            alias_db: AliasDbConfig {
+                writeable: true,
+                ..Default::default()
+            },
+            fasta_dir: FastaDirConfig {
+                writeable: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    )?;
+    let namespace = Namespace::new(&args.namespace);
+    for fasta_file in &args.fasta_files {
+        let stats = seq_repo.load_fasta(fasta_file, &namespace)?;
+        info!(
+            "{}: {} sequences, {} new",
+            fasta_file, stats.n_records, stats.n_sequences_added
+        );
+    }
+
+    Ok(())
+}
+
+/// Parsing of "search" subcommand
+#[derive(Debug, Args)]
+struct SearchArgs {
+    /// The namespace to search in, e.g., "refseq" or "ensembl".
+    #[arg(short, long)]
+    pub namespace: Option<String>,
+    /// The instance name to use.
+    #[arg(short, long, default_value = "latest")]
+    pub instance_name: String,
+    /// Also return aliases that are not current.
+    #[arg(long)]
+    pub include_retired: bool,
+    /// The maximal number of records to return per pattern.
+    #[arg(long)]
+    pub limit: Option<usize>,
+    /// The alias patterns, with "%" or "*" as wildcards, e.g., "NM_0013044*".
+    #[arg(required = true)]
+    pub patterns: Vec<String>,
+}
+
+/// Implementation of "search" command.
+fn main_search(
+    common_args: &CommonArgs,
+    args: &SearchArgs,
+    out: &mut impl Write,
+) -> Result<(), Error> {
+    debug!("common_args = {:?}", &common_args);
+    debug!("args = {:?}", &args);
+
+    let seq_repo = SeqRepo::new(&common_args.root_directory, &args.instance_name)?;
+    for pattern in &args.patterns {
+        let query = Query {
+            namespace: args.namespace.as_deref().map(Namespace::normalize),
+            alias: Some(pattern.replace('*', "%")),
+            current_only: !args.include_retired,
+            order_by: OrderBy::Alias,
+            limit: args.limit,
+            ..Default::default()
+        };
+        for record in seq_repo.alias_db().find_all(&query)? {
+            writeln!(
+                out,
+                "{}\t{}\t{}\t{}\t{}",
+                record.seqid, *record.namespace, record.alias, record.added, record.is_current
+            )
+            .map_err(output_error)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Parsing of "serve" subcommand
+#[cfg(feature = "server")]
+#[derive(Debug, Args)]
+struct ServeArgs {
+    /// The instance name to use.
+    #[arg(short, long, default_value = "latest")]
+    pub instance_name: String,
+    /// The address to listen on.
+    #[arg(short, long, default_value = "127.0.0.1:8080")]
+    pub listen: String,
+}
+
+/// Implementation of "serve" command.
+#[cfg(feature = "server")]
+fn main_serve(common_args: &CommonArgs, args: &ServeArgs) -> Result<(), Error> {
+    debug!("common_args = {:?}", &common_args);
+    debug!("args = {:?}", &args);
+
+    let seq_repo = std::sync::Arc::new(SeqRepo::new(
+        &common_args.root_directory,
+        &args.instance_name,
+    )?);
+    let runtime = tokio::runtime::Runtime::new().map_err(|e| Error::Server(e.to_string()))?;
+    runtime.block_on(async {
+        let listener = tokio::net::TcpListener::bind(&args.listen)
+            .await
+            .map_err(|e| Error::Server(format!("{}: {e}", args.listen)))?;
+        info!("serving refget API on http://{}", args.listen);
+        seqrepo::server::serve(seq_repo, Default::default(), listener).await
+    })
+}
+
+/// Parsing of "translate" subcommand
+#[derive(Debug, Args)]
+struct TranslateArgs {
+    /// The instance name to use.
+    #[arg(short, long, default_value = "latest")]
+    pub instance_name: String,
+    /// The namespaces to translate to, e.g., "refseq,ga4gh", defaults to all.
+    #[arg(short, long, value_delimiter = ',')]
+    pub target_namespaces: Vec<String>,
+    /// The identifiers to translate, e.g., "refseq:NM_000551.3".
+    #[arg(required = true)]
+    pub identifiers: Vec<String>,
+}
+
+/// Implementation of "translate" command.
+fn main_translate(
+    common_args: &CommonArgs,
+    args: &TranslateArgs,
+    out: &mut impl Write,
+) -> Result<(), Error> {
+    debug!("common_args = {:?}", &common_args);
+    debug!("args = {:?}", &args);
+
+    let seq_repo = SeqRepo::new(&common_args.root_directory, &args.instance_name)?;
+    let target_namespaces = args
+        .target_namespaces
+        .iter()
+        .map(String::as_str)
+        .collect::<Vec<_>>();
+    let target_namespaces = (!target_namespaces.is_empty()).then_some(target_namespaces.as_slice());
+
+    for identifier in &args.identifiers {
+        for translated in seq_repo.translate_identifier(identifier, target_namespaces)? {
+            writeln!(out, "{identifier}\t{translated}").map_err(output_error)?;
+        }
+    }
+
+    Ok(())
+}
+
+pub fn main() -> Result<(), Error> {
+    let cli = Cli::parse();
+
+    // Build a tracing subscriber according to the configuration in `cli.common`.
+    let collector = tracing_subscriber::fmt()
+        .with_target(false)
+        .with_max_level(match cli.common.verbose.log_level() {
+            Some(level) => match level {
+                log::Level::Error => tracing::Level::ERROR,
+                log::Level::Warn => tracing::Level::WARN,
+                log::Level::Info => tracing::Level::INFO,
+                log::Level::Debug => tracing::Level::DEBUG,
+                log::Level::Trace => tracing::Level::TRACE,
+            },
+            None => tracing::Level::INFO,
+        })
+        .compact()
+        .finish();
+
+    tracing::subscriber::with_default(collector, || {
+        let out = &mut std::io::stdout().lock();
+        match &cli.command {
+            Commands::Export(args) => {
+                main_export(&cli.common, args)?;
+            }
+            Commands::Fetch(args) => {
+                main_fetch(&cli.common, args, out)?;
+            }
+            Commands::Info(args) => {
+                main_info(&cli.common, args, out)?;
+            }
+            Commands::Init(args) => {
+                main_init(&cli.common, args)?;
+            }
+            Commands::ListInstances => {
+                main_list_instances(&cli.common, out)?;
+            }
+            Commands::Load(args) => {
+                main_load(&cli.common, args)?;
+            }
+            Commands::Search(args) => {
+                main_search(&cli.common, args, out)?;
+            }
+            #[cfg(feature = "server")]
+            Commands::Serve(args) => {
+                main_serve(&cli.common, args)?;
+            }
+            Commands::Translate(args) => {
+                main_translate(&cli.common, args, out)?;
+            }
+        }
+
+        Ok::<(), Error>(())
+    })?;
+
+    debug!("All done! Have a nice day.");
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use clap_verbosity_flag::Verbosity;
+    use seqrepo::Error;
+
+    use super::{
+        main_export, main_fetch, main_info, main_list_instances, main_search, main_translate,
+    };
+    use crate::{CommonArgs, ExportArgs, FetchArgs, InfoArgs, SearchArgs, TranslateArgs};
+
+    fn common_args() -> CommonArgs {
+        CommonArgs {
+            verbose: Verbosity::new(0, 0),
+            root_directory: "tests/data/seqrepo".to_string(),
+        }
+    }
+
+    #[test]
+    fn run_cmd() -> Result<(), Error> {
+        main_export(
+            &common_args(),
+            &ExportArgs {
+                namespace: None,
+                instance_name: "latest".to_string(),
+                aliases: vec!["XR_001757199.1".to_string()],
+            },
+        )
+    }
+
+    #[test]
+    fn run_fetch() -> Result<(), Error> {
+        let mut out = Vec::new();
+        main_fetch(
+            &common_args(),
+            &FetchArgs {
+                instance_name: "latest".to_string(),
+                zero_based: false,
+                reverse: true,
+                regions: vec![
+                    "refseq:NM_001304430.2:1-10".to_string(),
+                    "NM_001304430.2:1871-".to_string(),
+                ],
+            },
+            &mut out,
+        )?;
+        assert_eq!(String::from_utf8_lossy(&out), "GCTCAGCAGT\nTAT\n");
+        assert!(main_fetch(
+            &common_args(),
+            &FetchArgs {
+                instance_name: "latest".to_string(),
+                zero_based: true,
+                reverse: false,
+                regions: vec!["NM_001304430.2:0-2000".to_string()],
+            },
+            &mut Vec::new(),
+        )
+        .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn run_info() -> Result<(), Error> {
+        let mut out = Vec::new();
+        main_info(
+            &common_args(),
+            &InfoArgs {
+                instance_name: "latest".to_string(),
+                identifiers: vec!["refseq:NM_001304430.2".to_string()],
+            },
+            &mut out,
+        )?;
+        assert_eq!(
+            String::from_utf8_lossy(&out),
+            "identifier\trefseq:NM_001304430.2\n\
+            seq_id\t5q5HZTCRudL17NTiv5Bn6th__0FrZH04\n\
+            length\t1873\n\
+            alphabet\tACGT\n\
+            added\t2023-02-16 09:46:06\n\
+            alias\tMD5:a8e7e4cbd2fa521b45b23692b2dd601c\n\
+            alias\tNCBI:NM_001304430.2\n\
+            alias\tSEGUID:U5AvKXlRSRwJgn/Zxsa286iO/sg\n\
+            alias\tSHA1:53902f297951491c09827fd9c6c6b6f3a88efec8\n\
+            alias\tVMC:GS_5q5HZTCRudL17NTiv5Bn6th__0FrZH04\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn run_search() -> Result<(), Error> {
+        let mut out = Vec::new();
+        main_search(
+            &common_args(),
+            &SearchArgs {
+                namespace: Some("refseq".to_string()),
+                instance_name: "latest".to_string(),
+                include_retired: false,
+                limit: Some(10),
+                patterns: vec!["NM_0013044*".to_string()],
+            },
+            &mut out,
+        )?;
+        assert_eq!(
+            String::from_utf8_lossy(&out),
+            "5q5HZTCRudL17NTiv5Bn6th__0FrZH04\tNCBI\tNM_001304430.2\t2023-02-16 09:46:06\ttrue\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn run_translate() -> Result<(), Error> {
+        let mut out = Vec::new();
+        main_translate(
+            &common_args(),
+            &TranslateArgs {
+                instance_name: "latest".to_string(),
+                target_namespaces: vec!["ga4gh".to_string(), "md5".to_string()],
+                identifiers: vec!["refseq:NM_001304430.2".to_string()],
+            },
+            &mut out,
+        )?;
+        assert_eq!(
+            String::from_utf8_lossy(&out),
+            "refseq:NM_001304430.2\tMD5:a8e7e4cbd2fa521b45b23692b2dd601c\n\
+            refseq:NM_001304430.2\tga4gh:SQ.5q5HZTCRudL17NTiv5Bn6th__0FrZH04\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn run_list_instances() -> Result<(), Error> {
+        let mut out = Vec::new();
+        main_list_instances(&common_args(), &mut out)?;
+        assert_eq!(String::from_utf8_lossy(&out), "latest\n");
+
+        Ok(())
+    }
+}
+
+// <LICENSE>
+// Copyright 2023 seqrepo-rs Contributors
+// Copyright 2016 biocommons.seqrepo Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+// </LICENSE>
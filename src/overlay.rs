@@ -0,0 +1,169 @@
+//! View on several instances of a seqrepo, e.g., of different snapshots.
+
+use std::path::Path;
+
+use crate::error::Error;
+use crate::fallback::FallbackChainSeqRepo;
+use crate::repo::{SeqRepo, SeqRepoConfig};
+
+/// Sequence repository resolving aliases across several instances in priority order.
+///
+/// Each call is answered by the first instance that knows the sequence, i.e., that
+/// does not fail with an error for which `Error::is_not_found()` holds.  For example,
+/// with the instances `latest` and `2021-01-29`, transcripts retired from the newest
+/// snapshot can still be fetched from the older one.
+pub type OverlaySeqRepo = FallbackChainSeqRepo<SeqRepo>;
+
+impl FallbackChainSeqRepo<SeqRepo> {
+    /// Open the `instances` below the root directory at `path`, in priority order.
+    pub fn from_instances<P>(path: P, instances: &[&str]) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+    {
+        Self::from_instances_with_config(path, instances, Default::default())
+    }
+
+    /// Open the `instances` with the given configuration, in priority order.
+    pub fn from_instances_with_config<P>(
+        path: P,
+        instances: &[&str],
+        config: SeqRepoConfig,
+    ) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+    {
+        Self::new(
+            instances
+                .iter()
+                .map(|instance| SeqRepo::new_with_config(path.as_ref(), instance, config.clone()))
+                .collect::<Result<Vec<_>, _>>()?,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+
+    use pretty_assertions::assert_eq;
+    use temp_testdir::TempDir;
+
+    use super::OverlaySeqRepo;
+    use crate::{AliasOrSeqId, Error, Interface, Namespace, NamespacedAlias, SeqRepo, Strand};
+
+    /// Create instance `instance` with `seq` stored as `NM_1.1`, and `extra` if given.
+    fn create_instance(
+        root_dir: &Path,
+        instance: &str,
+        seq: &str,
+        extra: Option<(&str, &str)>,
+    ) -> Result<(), anyhow::Error> {
+        let sr = SeqRepo::init(root_dir, instance)?;
+        let alias = |alias: &str| NamespacedAlias {
+            namespace: Namespace::new("NCBI"),
+            alias: alias.to_string(),
+        };
+        sr.store(seq, &[alias("NM_1.1")])?;
+        if let Some((alias_value, seq)) = extra {
+            sr.store(seq, &[alias(alias_value)])?;
+        }
+        sr.commit()?;
+        Ok(())
+    }
+
+    /// Mark the alias `alias` in instance `instance` as retired.
+    fn retire_alias(root_dir: &Path, instance: &str, alias: &str) -> Result<(), anyhow::Error> {
+        let conn = rusqlite::Connection::open(root_dir.join(instance).join("aliases.sqlite3"))?;
+        let n = conn.execute(
+            "UPDATE seqalias SET is_current = 0 WHERE alias = ?",
+            [alias],
+        )?;
+        assert_eq!(n, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_sync() {
+        fn is_sync<T: Sync>() {}
+        is_sync::<super::OverlaySeqRepo>();
+    }
+
+    #[test]
+    fn priority_order() -> Result<(), anyhow::Error> {
+        let temp = TempDir::default();
+        create_instance(
+            temp.as_ref(),
+            "2021-01-29",
+            "ACGT",
+            Some(("NM_2.1", "GATTACA")),
+        )?;
+        create_instance(
+            temp.as_ref(),
+            "2024-02-20",
+            "ACGTACGT",
+            Some(("NM_3.1", "TTAGG")),
+        )?;
+        create_instance(
+            temp.as_ref(),
+            "2024-06-01",
+            "ACGTACGT",
+            Some(("NM_2.1", "CCCC")),
+        )?;
+        retire_alias(temp.as_ref(), "2024-06-01", "NM_2.1")?;
+
+        let aos: AliasOrSeqId = "refseq:NM_1.1".parse()?;
+        let retired: AliasOrSeqId = "refseq:NM_2.1".parse()?;
+
+        let overlay = OverlaySeqRepo::from_instances(temp.as_ref(), &["2024-06-01", "2021-01-29"])?;
+        assert_eq!(overlay.repos().len(), 2);
+        assert_eq!(overlay.fetch_sequence(&aos)?, "ACGTACGT");
+        // Present but retired in the newest instance, current in the older one.
+        assert_eq!(overlay.fetch_sequence(&retired)?, "GATTACA");
+        assert_eq!(overlay.sequence_info(&retired)?.len, 7);
+        assert_eq!(
+            overlay.fetch_sequence_part_stranded(&retired, Some(0), Some(3), Strand::Reverse)?,
+            "ATC"
+        );
+        assert!(overlay.contains_sequence("ACGT")?);
+        assert!(!overlay.contains_sequence("TTTT")?);
+        assert!(matches!(
+            overlay.fetch_sequence(&"refseq:NM_4.1".parse()?),
+            Err(Error::AliasDbResolve(_))
+        ));
+
+        // Only stored in the older instance.
+        let overlay = OverlaySeqRepo::from_instances(temp.as_ref(), &["2024-02-20", "2021-01-29"])?;
+        assert_eq!(overlay.fetch_sequence(&retired)?, "GATTACA");
+        assert_eq!(overlay.fetch_sequence(&"refseq:NM_3.1".parse()?)?, "TTAGG");
+
+        let overlay = OverlaySeqRepo::from_instances(temp.as_ref(), &["2021-01-29", "2024-02-20"])?;
+        assert_eq!(overlay.fetch_sequence(&aos)?, "ACGT");
+
+        Ok(())
+    }
+
+    #[test]
+    fn no_instances() {
+        assert!(OverlaySeqRepo::new(Vec::new()).is_err());
+        assert!(
+            OverlaySeqRepo::from_instances("tests/data/seqrepo", &["latest", "missing"]).is_err()
+        );
+    }
+}
+
+// <LICENSE>
+// Copyright 2023 seqrepo-rs Contributors
+// Copyright 2016 biocommons.seqrepo Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+// </LICENSE>
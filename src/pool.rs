@@ -0,0 +1,251 @@
+//! Pooling of SQLite connections.
+//!
+//! A single connection serializes all queries of concurrent threads.  The pool
+//! opens up to a configurable number of connections on demand and hands them out
+//! to one thread at a time.
+
+use std::{
+    ops::{Deref, DerefMut},
+    path::{Path, PathBuf},
+    sync::{Condvar, Mutex},
+};
+
+use rusqlite::{Connection, OpenFlags};
+
+use crate::error::Error;
+
+/// Default number of connections per pool, the available parallelism.
+pub(crate) fn default_pool_size() -> usize {
+    std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(4)
+}
+
+/// Connections of a `ConnectionPool` and their bookkeeping.
+#[derive(Debug, Default)]
+struct PoolState {
+    /// Connections that are currently not in use.
+    idle: Vec<Connection>,
+    /// Number of connections opened, in use or idle.
+    n_open: usize,
+}
+
+/// Pool of connections to one SQLite database.
+#[derive(Debug)]
+pub(crate) struct ConnectionPool {
+    /// Path to the database file.
+    path: PathBuf,
+    /// Flags for opening connections.
+    flags: OpenFlags,
+    /// Maximal number of connections to open.
+    capacity: usize,
+    /// Constructor of the error when opening a connection fails.
+    connect_error: fn(String) -> Error,
+    /// The connections.
+    state: Mutex<PoolState>,
+    /// Signalled when a connection is returned to the pool.
+    returned: Condvar,
+}
+
+impl ConnectionPool {
+    /// Create pool of at most `capacity` connections to the database at `path`.
+    ///
+    /// One connection is opened right away so that errors surface early.  A
+    /// `capacity` of `0` is treated as `1`.
+    pub(crate) fn new(
+        path: &Path,
+        flags: OpenFlags,
+        capacity: usize,
+        connect_error: fn(String) -> Error,
+    ) -> Result<Self, Error> {
+        let pool = Self {
+            path: path.to_path_buf(),
+            flags: flags | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+            capacity: std::cmp::max(capacity, 1),
+            connect_error,
+            state: Default::default(),
+            returned: Condvar::new(),
+        };
+        let conn = pool.connect()?;
+        {
+            let mut state = pool.state.lock().map_err(|_| Error::MutexSqlite)?;
+            state.idle.push(conn);
+            state.n_open = 1;
+        }
+        Ok(pool)
+    }
+
+    /// Obtain a connection, waiting for one to be returned if all are in use.
+    pub(crate) fn get(&self) -> Result<PooledConnection<'_>, Error> {
+        let mut state = self.state.lock().map_err(|_| Error::MutexSqlite)?;
+        loop {
+            if let Some(conn) = state.idle.pop() {
+                return Ok(PooledConnection {
+                    pool: self,
+                    conn: Some(conn),
+                });
+            }
+            if state.n_open < self.capacity {
+                state.n_open += 1;
+                drop(state);
+                // Open the connection without holding the lock.
+                return match self.connect() {
+                    Ok(conn) => Ok(PooledConnection {
+                        pool: self,
+                        conn: Some(conn),
+                    }),
+                    Err(e) => {
+                        if let Ok(mut state) = self.state.lock() {
+                            state.n_open -= 1;
+                        }
+                        Err(e)
+                    }
+                };
+            }
+            state = self.returned.wait(state).map_err(|_| Error::MutexSqlite)?;
+        }
+    }
+
+    /// Open a new connection.
+    fn connect(&self) -> Result<Connection, Error> {
+        Connection::open_with_flags(&self.path, self.flags)
+            .map_err(|e| (self.connect_error)(e.to_string()))
+    }
+
+    /// Return `conn` to the pool.
+    fn put(&self, conn: Connection) {
+        if let Ok(mut state) = self.state.lock() {
+            state.idle.push(conn);
+            self.returned.notify_one();
+        }
+    }
+}
+
+/// A connection obtained from a `ConnectionPool`, returned to it on drop.
+#[derive(Debug)]
+pub(crate) struct PooledConnection<'a> {
+    /// The pool to return the connection to.
+    pool: &'a ConnectionPool,
+    /// The connection, only `None` while dropping.
+    conn: Option<Connection>,
+}
+
+impl Deref for PooledConnection<'_> {
+    type Target = Connection;
+
+    fn deref(&self) -> &Self::Target {
+        self.conn.as_ref().expect("connection taken")
+    }
+}
+
+impl DerefMut for PooledConnection<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.conn.as_mut().expect("connection taken")
+    }
+}
+
+impl Drop for PooledConnection<'_> {
+    fn drop(&mut self) {
+        if let Some(conn) = self.conn.take() {
+            self.pool.put(conn);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    use pretty_assertions::assert_eq;
+    use rusqlite::OpenFlags;
+
+    use super::ConnectionPool;
+    use crate::error::Error;
+
+    fn open(capacity: usize) -> Result<ConnectionPool, Error> {
+        ConnectionPool::new(
+            Path::new("tests/data/aliases/aliases.sqlite3"),
+            OpenFlags::SQLITE_OPEN_READ_ONLY,
+            capacity,
+            Error::AliasDbConnect,
+        )
+    }
+
+    #[test]
+    fn test_sync() {
+        fn is_sync<T: Sync>() {}
+        is_sync::<ConnectionPool>();
+    }
+
+    #[test]
+    fn reuse_connections() -> Result<(), anyhow::Error> {
+        let pool = open(2)?;
+        {
+            let first = pool.get()?;
+            let second = pool.get()?;
+            let count: u64 =
+                first.query_row("select count(*) from seqalias", [], |row| row.get(0))?;
+            assert_eq!(count, 5);
+            drop(second);
+        }
+        assert_eq!(pool.state.lock().unwrap().n_open, 2);
+        assert_eq!(pool.state.lock().unwrap().idle.len(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn wait_for_returned() -> Result<(), anyhow::Error> {
+        let pool = open(1)?;
+        let conn = pool.get()?;
+
+        std::thread::scope(|s| {
+            let pool = &pool;
+            let (tx, rx) = mpsc::channel();
+            s.spawn(move || {
+                let _conn = pool.get().unwrap();
+                tx.send(()).unwrap();
+            });
+            // The other thread must wait until the connection is returned.
+            assert!(rx.recv_timeout(Duration::from_millis(100)).is_err());
+            drop(conn);
+            assert!(rx.recv_timeout(Duration::from_secs(10)).is_ok());
+        });
+        assert_eq!(pool.state.lock().unwrap().n_open, 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn capacity_at_least_one() -> Result<(), anyhow::Error> {
+        assert_eq!(open(0)?.capacity, 1);
+        assert!(ConnectionPool::new(
+            Path::new("tests/data/does-not-exist.sqlite3"),
+            OpenFlags::SQLITE_OPEN_READ_ONLY,
+            1,
+            Error::AliasDbConnect,
+        )
+        .is_err());
+
+        Ok(())
+    }
+}
+
+// <LICENSE>
+// Copyright 2023 seqrepo-rs Contributors
+// Copyright 2016 biocommons.seqrepo Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+// </LICENSE>
@@ -0,0 +1,556 @@
+//! Sequence repository reading from a GA4GH refget v2 server.
+//!
+//! This allows for using a remote server, e.g., one run with the `server` module,
+//! instead of a local seqrepo snapshot.  Combine with `CacheWritingSeqRepo` to record
+//! the sequences used by tests from a remote server.
+
+use std::io::Read;
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use crate::aliases::GA4GH_PREFIX;
+use crate::digest;
+use crate::error::Error;
+use crate::interface::{AliasOrSeqId, Interface, SequenceInfo};
+use crate::{Namespace, NamespacedAlias};
+
+/// Media type of sequence responses.
+static ACCEPT_SEQUENCE: &str = "text/vnd.ga4gh.refget.v2.0.0+plain";
+/// Media type of metadata responses.
+static ACCEPT_JSON: &str = "application/vnd.ga4gh.refget.v2.0.0+json";
+
+/// Configuration for accessing a refget server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RefgetSeqRepoConfig {
+    /// Timeout for each request, including reading the response.
+    pub timeout: Duration,
+}
+
+impl Default for RefgetSeqRepoConfig {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(60),
+        }
+    }
+}
+
+/// Sequence repository reading from a refget v2 server.
+///
+/// Sequences are requested with `SQ.<seq_id>` for `seq_id`s and the plain digest
+/// for MD5 aliases.  Other aliases are sent as `namespace:alias`, e.g.,
+/// `refseq:NM_000551.3`, which requires the server to resolve them.
+///
+/// Refget does not provide the alphabet and time of addition of sequences, so
+/// these are `None` in the results of `sequence_info()`.
+#[derive(Debug, Clone)]
+pub struct RefgetSeqRepo {
+    /// Base URL of the server, without trailing slash.
+    base_url: String,
+    /// The HTTP agent, sharing connections between requests.
+    agent: ureq::Agent,
+}
+
+impl RefgetSeqRepo {
+    /// Create new `RefgetSeqRepo` for the server at `base_url`, e.g.,
+    /// `https://refget.example.org` for `https://refget.example.org/sequence/...`.
+    pub fn new(base_url: &str) -> Result<Self, Error> {
+        Self::new_with_config(base_url, Default::default())
+    }
+
+    /// Create new `RefgetSeqRepo` with the given configuration.
+    pub fn new_with_config(base_url: &str, config: RefgetSeqRepoConfig) -> Result<Self, Error> {
+        if !base_url.starts_with("http://") && !base_url.starts_with("https://") {
+            return Err(Error::RefgetRequest(format!(
+                "invalid base URL: {base_url}"
+            )));
+        }
+        Ok(Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            agent: ureq::AgentBuilder::new().timeout(config.timeout).build(),
+        })
+    }
+
+    /// Provide access to the base URL of the server.
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Perform `GET` request for `path` of the sequence `identifier`, return the body.
+    fn get(
+        &self,
+        identifier: &str,
+        path: &str,
+        query: &[(&str, usize)],
+        accept: &str,
+    ) -> Result<String, Error> {
+        let mut request = self
+            .agent
+            .get(&format!("{}{}", self.base_url, path))
+            .set("Accept", accept);
+        for (name, value) in query {
+            request = request.query(name, &value.to_string());
+        }
+
+        let response = match request.call() {
+            Ok(response) => response,
+            Err(ureq::Error::Status(404, _)) => {
+                return Err(Error::RefgetNotFound(identifier.to_string()))
+            }
+            Err(ureq::Error::Status(status, response)) => {
+                return Err(Error::RefgetStatus(
+                    status,
+                    response.into_string().unwrap_or_default(),
+                ))
+            }
+            Err(e) => return Err(Error::RefgetRequest(e.to_string())),
+        };
+
+        // Read without the size limit of `into_string()`, sequences may be large.
+        let mut body = String::new();
+        response
+            .into_reader()
+            .read_to_string(&mut body)
+            .map_err(|e| Error::RefgetRequest(e.to_string()))?;
+        Ok(body)
+    }
+
+    /// Fetch the metadata of the sequence given an alias.
+    fn metadata(&self, alias_or_seq_id: &AliasOrSeqId) -> Result<Metadata, Error> {
+        let identifier = refget_identifier(alias_or_seq_id);
+        let body = self.get(
+            &identifier,
+            &format!("/sequence/{}/metadata", encode_path_segment(&identifier)),
+            &[],
+            ACCEPT_JSON,
+        )?;
+        serde_json::from_str::<MetadataResponse>(&body)
+            .map(|response| response.metadata)
+            .map_err(|e| Error::RefgetResponse(e.to_string()))
+    }
+
+    /// Describe the range rejected by the server if the metadata confirms it is out of bounds.
+    fn range_error(
+        &self,
+        alias_or_seq_id: &AliasOrSeqId,
+        identifier: &str,
+        begin: Option<usize>,
+        end: Option<usize>,
+    ) -> Option<Error> {
+        let len = self.metadata(alias_or_seq_id).ok()?.length;
+        let begin = begin.unwrap_or(0);
+        let end = end.unwrap_or(len).max(begin);
+        (end > len).then(|| Error::RangeOutOfBounds(identifier.to_string(), begin, end, len))
+    }
+}
+
+impl Interface for RefgetSeqRepo {
+    fn fetch_sequence_part(
+        &self,
+        alias_or_seq_id: &AliasOrSeqId,
+        begin: Option<usize>,
+        end: Option<usize>,
+    ) -> Result<String, Error> {
+        let identifier = refget_identifier(alias_or_seq_id);
+        // Refget interprets `start > end` as range across the origin of circular sequences.
+        if let (Some(begin), Some(end)) = (begin, end) {
+            if begin > end {
+                return Err(Error::InvalidRange(identifier, begin, end));
+            }
+        }
+
+        let query = [("start", begin), ("end", end)]
+            .into_iter()
+            .filter_map(|(name, value)| value.map(|value| (name, value)))
+            .collect::<Vec<_>>();
+        let result = self.get(
+            &identifier,
+            &format!("/sequence/{}", encode_path_segment(&identifier)),
+            &query,
+            ACCEPT_SEQUENCE,
+        );
+        match result {
+            Err(Error::RefgetStatus(416, body)) => Err(self
+                .range_error(alias_or_seq_id, &identifier, begin, end)
+                .unwrap_or(Error::RefgetStatus(416, body))),
+            result => result,
+        }
+    }
+
+    fn sequence_info(&self, alias_or_seq_id: &AliasOrSeqId) -> Result<SequenceInfo, Error> {
+        let metadata = self.metadata(alias_or_seq_id)?;
+        let seq_id = match metadata
+            .ga4gh
+            .as_deref()
+            .and_then(|ga4gh| ga4gh.strip_prefix(GA4GH_PREFIX))
+        {
+            Some(seq_id) => seq_id.to_string(),
+            None => digest::seq_sha512t24u(&self.fetch_sequence(alias_or_seq_id)?)?,
+        };
+
+        let mut aliases = metadata
+            .aliases
+            .into_iter()
+            .map(|alias| NamespacedAlias {
+                namespace: Namespace::normalize(&alias.naming_authority),
+                alias: alias.alias,
+            })
+            .collect::<Vec<_>>();
+        if let Some(md5) = metadata.md5 {
+            if !aliases
+                .iter()
+                .any(|alias| alias.namespace.value == Namespace::MD5)
+            {
+                aliases.push(NamespacedAlias {
+                    namespace: Namespace::new(Namespace::MD5),
+                    alias: md5,
+                });
+            }
+        }
+        aliases.sort_by(|a, b| (&a.namespace.value, &a.alias).cmp(&(&b.namespace.value, &b.alias)));
+
+        Ok(SequenceInfo {
+            seq_id,
+            len: metadata.length,
+            alphabet: None,
+            added: None,
+            aliases,
+        })
+    }
+
+    fn find_sequence(&self, seq: &str) -> Result<Option<String>, Error> {
+        let seq_id = self.seq_id_for(seq)?;
+        match self.metadata(&AliasOrSeqId::SeqId(seq_id.clone())) {
+            Ok(_) => Ok(Some(seq_id)),
+            Err(e) if e.is_not_found() => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// Response of the metadata endpoint.
+#[derive(Debug, Deserialize)]
+struct MetadataResponse {
+    metadata: Metadata,
+}
+
+/// Metadata of a sequence as returned by refget.
+#[derive(Debug, Deserialize)]
+struct Metadata {
+    md5: Option<String>,
+    ga4gh: Option<String>,
+    length: usize,
+    #[serde(default)]
+    aliases: Vec<MetadataAlias>,
+}
+
+/// Alias of a sequence in the refget metadata.
+#[derive(Debug, Deserialize)]
+struct MetadataAlias {
+    alias: String,
+    naming_authority: String,
+}
+
+/// Map `alias_or_seq_id` to the identifier used in refget requests.
+fn refget_identifier(alias_or_seq_id: &AliasOrSeqId) -> String {
+    match alias_or_seq_id {
+        AliasOrSeqId::SeqId(seq_id) => format!("{GA4GH_PREFIX}{seq_id}"),
+        AliasOrSeqId::Alias {
+            value,
+            namespace: Some(namespace),
+        } if Namespace::normalize(namespace).value == Namespace::MD5 => value.clone(),
+        alias_or_seq_id => alias_or_seq_id.to_string(),
+    }
+}
+
+/// Percent-encode `s` for use as URL path segment, keeping `:` for readability.
+fn encode_path_segment(s: &str) -> String {
+    s.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b':' => {
+                (b as char).to_string()
+            }
+            b => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+
+    use pretty_assertions::assert_eq;
+
+    use super::{encode_path_segment, refget_identifier, RefgetSeqRepo};
+    use crate::{AliasOrSeqId, Error, Interface, SeqRepo};
+
+    static SEQ_ID: &str = "5q5HZTCRudL17NTiv5Bn6th__0FrZH04";
+    static METADATA: &str = r#"{"metadata": {
+        "md5": "a8e7e4cbd2fa521b45b23692b2dd601c",
+        "ga4gh": "SQ.5q5HZTCRudL17NTiv5Bn6th__0FrZH04",
+        "length": 1873,
+        "aliases": [{"alias": "NM_001304430.2", "naming_authority": "refseq"}]
+    }}"#;
+
+    /// Start a stand-in refget server answering request paths with canned responses.
+    fn start(responses: Vec<(String, u16, String)>) -> Result<String, anyhow::Error> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let addr = listener.local_addr()?;
+        let responses = responses
+            .into_iter()
+            .map(|(path, status, body)| (path, (status, body)))
+            .collect::<HashMap<_, _>>();
+
+        std::thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                let mut reader = BufReader::new(stream.try_clone().expect("could not clone"));
+                let mut request_line = String::new();
+                let _ = reader.read_line(&mut request_line);
+                let mut line = String::new();
+                while reader.read_line(&mut line).is_ok_and(|n| n > 2) {
+                    line.clear();
+                }
+
+                let path = request_line.split(' ').nth(1).unwrap_or_default();
+                let (status, body) = responses
+                    .get(path)
+                    .cloned()
+                    .unwrap_or((404, "not found".to_string()));
+                let _ = write!(
+                    stream,
+                    "HTTP/1.1 {status} Status\r\nContent-Length: {}\r\n\
+                     Connection: close\r\n\r\n{body}",
+                    body.len()
+                );
+            }
+        });
+
+        Ok(format!("http://{addr}/"))
+    }
+
+    /// Start a stand-in server for the test sequence `NM_001304430.2`.
+    fn start_default() -> Result<(String, String), anyhow::Error> {
+        let sr = SeqRepo::new("tests/data/seqrepo", "latest")?;
+        let seq = sr.fetch_sequence(&AliasOrSeqId::SeqId(SEQ_ID.to_string()))?;
+        let url = start(vec![
+            (format!("/sequence/SQ.{SEQ_ID}"), 200, seq.clone()),
+            (
+                format!("/sequence/SQ.{SEQ_ID}?start=0&end=10"),
+                200,
+                seq[..10].to_string(),
+            ),
+            (
+                "/sequence/refseq:NM_001304430.2?start=1870".to_string(),
+                200,
+                seq[1870..].to_string(),
+            ),
+            (
+                "/sequence/a8e7e4cbd2fa521b45b23692b2dd601c?end=3".to_string(),
+                200,
+                seq[..3].to_string(),
+            ),
+            (
+                "/sequence/refseq:NM_001304430.2?start=0&end=2000".to_string(),
+                416,
+                "range out of bounds".to_string(),
+            ),
+            (
+                "/sequence/a8e7e4cbd2fa521b45b23692b2dd601c?start=2000".to_string(),
+                416,
+                "range out of bounds".to_string(),
+            ),
+            (
+                "/sequence/refseq:NM_001304430.2?start=5&end=10".to_string(),
+                416,
+                "range not satisfiable".to_string(),
+            ),
+            (
+                "/sequence/refseq:NM_001304430.2/metadata".to_string(),
+                200,
+                METADATA.to_string(),
+            ),
+            (
+                format!("/sequence/SQ.{SEQ_ID}/metadata"),
+                200,
+                METADATA.to_string(),
+            ),
+            (
+                "/sequence/refseq:broken/metadata".to_string(),
+                200,
+                "{}".to_string(),
+            ),
+        ])?;
+        Ok((url, seq))
+    }
+
+    #[test]
+    fn test_sync() {
+        fn is_sync<T: Sync>() {}
+        is_sync::<super::RefgetSeqRepo>();
+    }
+
+    #[test]
+    fn identifiers() -> Result<(), anyhow::Error> {
+        for (input, expected) in [
+            (
+                "ga4gh:SQ.5q5HZTCRudL17NTiv5Bn6th__0FrZH04",
+                "SQ.5q5HZTCRudL17NTiv5Bn6th__0FrZH04",
+            ),
+            (
+                "md5:a8e7e4cbd2fa521b45b23692b2dd601c",
+                "a8e7e4cbd2fa521b45b23692b2dd601c",
+            ),
+            ("NCBI:NM_001304430.2", "refseq:NM_001304430.2"),
+            ("NM_001304430.2", "NM_001304430.2"),
+        ] {
+            assert_eq!(refget_identifier(&input.parse()?), expected);
+        }
+        assert_eq!(
+            encode_path_segment("SEGUID:U5AvKXlRSRwJgn/Zxsa286iO/sg"),
+            "SEGUID:U5AvKXlRSRwJgn%2FZxsa286iO%2Fsg"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn fetch_sequence() -> Result<(), anyhow::Error> {
+        let (url, seq) = start_default()?;
+        let rr = RefgetSeqRepo::new(&url)?;
+        assert_eq!(rr.base_url(), url.trim_end_matches('/'));
+
+        let aos = AliasOrSeqId::SeqId(SEQ_ID.to_string());
+        assert_eq!(rr.fetch_sequence(&aos)?, seq);
+        assert_eq!(
+            rr.fetch_sequence_part(&aos, Some(0), Some(10))?,
+            "ACTGCTGAGC"
+        );
+        assert_eq!(
+            rr.fetch_sequence_part(&"refseq:NM_001304430.2".parse()?, Some(1870), None)?,
+            &seq[1870..]
+        );
+        assert_eq!(
+            rr.fetch_sequence_part(
+                &"md5:a8e7e4cbd2fa521b45b23692b2dd601c".parse()?,
+                None,
+                Some(3)
+            )?,
+            "ACT"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn fetch_sequence_errors() -> Result<(), anyhow::Error> {
+        let (url, _) = start_default()?;
+        let rr = RefgetSeqRepo::new(&url)?;
+
+        assert!(matches!(
+            rr.fetch_sequence(&"NM_000000.0".parse()?),
+            Err(Error::RefgetNotFound(id)) if id == "NM_000000.0"
+        ));
+        assert!(matches!(
+            rr.fetch_sequence_part(&"refseq:NM_001304430.2".parse()?, Some(0), Some(2000)),
+            Err(Error::RangeOutOfBounds(_, 0, 2000, 1873))
+        ));
+        // The server's response is kept unless the metadata confirms the range error.
+        assert!(matches!(
+            rr.fetch_sequence_part(
+                &"md5:a8e7e4cbd2fa521b45b23692b2dd601c".parse()?,
+                Some(2000),
+                None
+            ),
+            Err(Error::RefgetStatus(416, body)) if body == "range out of bounds"
+        ));
+        assert!(matches!(
+            rr.fetch_sequence_part(&"refseq:NM_001304430.2".parse()?, Some(5), Some(10)),
+            Err(Error::RefgetStatus(416, body)) if body == "range not satisfiable"
+        ));
+        assert!(matches!(
+            rr.fetch_sequence_part(&"refseq:NM_001304430.2".parse()?, Some(10), Some(5)),
+            Err(Error::InvalidRange(_, 10, 5))
+        ));
+        assert!(matches!(
+            rr.sequence_info(&"refseq:broken".parse()?),
+            Err(Error::RefgetResponse(_))
+        ));
+        assert!(matches!(
+            RefgetSeqRepo::new("localhost:8080"),
+            Err(Error::RefgetRequest(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn sequence_info() -> Result<(), anyhow::Error> {
+        let (url, seq) = start_default()?;
+        let rr = RefgetSeqRepo::new(&url)?;
+
+        let info = rr.sequence_info(&"refseq:NM_001304430.2".parse()?)?;
+        assert_eq!(info.seq_id, SEQ_ID);
+        assert_eq!(info.len, 1873);
+        assert_eq!(
+            info.aliases
+                .iter()
+                .map(|alias| alias.to_string())
+                .collect::<Vec<_>>(),
+            vec![
+                "MD5:a8e7e4cbd2fa521b45b23692b2dd601c",
+                "NCBI:NM_001304430.2"
+            ]
+        );
+
+        assert_eq!(rr.find_sequence(&seq)?, Some(SEQ_ID.to_string()));
+        assert!(rr.contains_sequence(&seq)?);
+        assert_eq!(rr.find_sequence("ACGT")?, None);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "cached")]
+    #[test]
+    fn cache_writing() -> Result<(), anyhow::Error> {
+        use crate::{CacheReadingSeqRepo, CacheWritingSeqRepo};
+
+        let (url, _) = start_default()?;
+        let temp = temp_testdir::TempDir::default();
+        let cache_path = temp.join("cache.fasta");
+
+        let aos = AliasOrSeqId::SeqId(SEQ_ID.to_string());
+        {
+            let cw = CacheWritingSeqRepo::new(RefgetSeqRepo::new(&url)?, &cache_path)?;
+            assert_eq!(
+                cw.fetch_sequence_part(&aos, Some(0), Some(10))?,
+                "ACTGCTGAGC"
+            );
+        }
+        let cr = CacheReadingSeqRepo::new(&cache_path)?;
+        assert_eq!(
+            cr.fetch_sequence_part(&aos, Some(0), Some(10))?,
+            "ACTGCTGAGC"
+        );
+
+        Ok(())
+    }
+}
+
+// <LICENSE>
+// Copyright 2023 seqrepo-rs Contributors
+// Copyright 2016 biocommons.seqrepo Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+// </LICENSE>
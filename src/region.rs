@@ -0,0 +1,261 @@
+//! Regions of sequences as given in strings such as `NC_000001.11:100-200`.
+
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::error::Error;
+use crate::interface::AliasOrSeqId;
+use crate::Namespace;
+
+/// Coordinate system of the positions in region strings.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum CoordinateSystem {
+    /// 1-based positions with both ends included, e.g., as in `samtools faidx`.
+    #[default]
+    OneBasedInclusive,
+    /// 0-based positions with the end excluded, e.g., as in BED files.
+    ZeroBasedHalfOpen,
+}
+
+/// A region of a sequence, given by alias or `seq_id` and an optional range.
+///
+/// The range is stored 0-based and half-open as in `Interface::fetch_sequence_part()`,
+/// missing ends refer to the start and end of the sequence.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Region {
+    /// The sequence of the region.
+    pub alias_or_seq_id: AliasOrSeqId,
+    /// 0-based start position, if any.
+    pub begin: Option<usize>,
+    /// 0-based end position (exclusive), if any.
+    pub end: Option<usize>,
+}
+
+impl Region {
+    /// Create region of the range `begin..end` of the given sequence.
+    pub fn new(alias_or_seq_id: AliasOrSeqId, begin: Option<usize>, end: Option<usize>) -> Self {
+        Self {
+            alias_or_seq_id,
+            begin,
+            end,
+        }
+    }
+
+    /// Parse region from `s` with positions in the given coordinate system.
+    ///
+    /// Accepts an identifier as parsed into `AliasOrSeqId`, optionally followed by
+    /// `:begin-end`, `:begin-` or `:begin` (up to the end of the sequence).  Positions
+    /// may contain `,` and `_` as thousands separators, e.g., `chr1:1,000-2,000`.
+    ///
+    /// A single position as in `chr1:5` is read as `:begin`, unless the part before it
+    /// is a known namespace, e.g., `GRCh38:1` denotes the alias `1` in namespace `GRCh38`.
+    pub fn parse(s: &str, coordinate_system: CoordinateSystem) -> Result<Self, Error> {
+        let s = s.trim();
+        let invalid = || Error::InvalidRegion(s.to_string());
+
+        let (identifier, range) = match s.rsplit_once(':') {
+            Some((identifier, range))
+                if is_range(range)
+                    && (range.contains('-')
+                        || identifier.contains(':')
+                        || !Namespace::is_known(identifier)) =>
+            {
+                (identifier, Some(range))
+            }
+            _ => (s, None),
+        };
+        let alias_or_seq_id = identifier.parse()?;
+        let Some(range) = range else {
+            return Ok(Self::new(alias_or_seq_id, None, None));
+        };
+
+        let (begin, end) = match range.split_once('-') {
+            Some((begin, end)) => (begin, Some(end).filter(|end| !end.is_empty())),
+            None => (range, None),
+        };
+        let begin = parse_position(begin).ok_or_else(invalid)?;
+        let end = end
+            .map(|end| parse_position(end).ok_or_else(invalid))
+            .transpose()?;
+
+        let begin = match coordinate_system {
+            CoordinateSystem::OneBasedInclusive => begin.checked_sub(1).ok_or_else(invalid)?,
+            CoordinateSystem::ZeroBasedHalfOpen => begin,
+        };
+        if end.is_some_and(|end| end < begin) {
+            return Err(invalid());
+        }
+
+        Ok(Self::new(alias_or_seq_id, Some(begin), end))
+    }
+}
+
+/// Parses 1-based inclusive regions, see `Region::parse()`.
+impl FromStr for Region {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s, CoordinateSystem::OneBasedInclusive)
+    }
+}
+
+/// Formats with 1-based inclusive positions, e.g., `refseq:NM_000551.3:1-100`.
+impl Display for Region {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.alias_or_seq_id)?;
+        match (self.begin, self.end) {
+            (None, None) => Ok(()),
+            (begin, None) => write!(f, ":{}-", one_based(begin)),
+            (begin, Some(end)) => write!(f, ":{}-{}", one_based(begin), end),
+        }
+    }
+}
+
+/// Convert the 0-based position `begin` (the start if `None`) to 1-based.
+fn one_based(begin: Option<usize>) -> u128 {
+    begin.unwrap_or(0) as u128 + 1
+}
+
+/// Whether `s` looks like the range part of a region string.
+fn is_range(s: &str) -> bool {
+    s.starts_with(|c: char| c.is_ascii_digit())
+        && s.chars()
+            .all(|c| c.is_ascii_digit() || c == ',' || c == '_' || c == '-')
+}
+
+/// Parse a position, skipping thousands separators.
+fn parse_position(s: &str) -> Option<usize> {
+    let digits: String = s.chars().filter(|&c| c != ',' && c != '_').collect();
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    digits.parse().ok()
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::{CoordinateSystem, Region};
+    use crate::AliasOrSeqId;
+
+    fn alias(value: &str, namespace: Option<&str>) -> AliasOrSeqId {
+        AliasOrSeqId::Alias {
+            value: value.to_string(),
+            namespace: namespace.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn parse() -> Result<(), anyhow::Error> {
+        assert_eq!(
+            "NC_000001.11:100-200".parse::<Region>()?,
+            Region::new(alias("NC_000001.11", None), Some(99), Some(200))
+        );
+        assert_eq!(
+            "chr1:1,000-2_000".parse::<Region>()?,
+            Region::new(alias("chr1", None), Some(999), Some(2000))
+        );
+        assert_eq!(
+            "refseq:NM_001304430.2:1-10".parse::<Region>()?,
+            Region::new(alias("NM_001304430.2", Some("NCBI")), Some(0), Some(10))
+        );
+        assert_eq!(
+            "ga4gh:SQ.5q5HZTCRudL17NTiv5Bn6th__0FrZH04:5-".parse::<Region>()?,
+            Region::new(
+                AliasOrSeqId::SeqId("5q5HZTCRudL17NTiv5Bn6th__0FrZH04".to_string()),
+                Some(4),
+                None
+            )
+        );
+        assert_eq!(
+            "refseq:NM_001304430.2:5".parse::<Region>()?,
+            Region::new(alias("NM_001304430.2", Some("NCBI")), Some(4), None)
+        );
+        assert_eq!(
+            "chr1:5-".parse::<Region>()?,
+            Region::new(alias("chr1", None), Some(4), None)
+        );
+        assert_eq!(
+            "chr1:5".parse::<Region>()?,
+            Region::new(alias("chr1", None), Some(4), None)
+        );
+        assert_eq!(
+            "NC_000001.11:100".parse::<Region>()?,
+            Region::new(alias("NC_000001.11", None), Some(99), None)
+        );
+        assert_eq!(
+            "grch38:1".parse::<Region>()?,
+            Region::new(alias("1", Some("GRCh38")), None, None)
+        );
+        assert_eq!(
+            "GRCh38:1".parse::<Region>()?,
+            Region::new(alias("1", Some("GRCh38")), None, None)
+        );
+        assert_eq!(
+            "GRCh38:1:100-200".parse::<Region>()?,
+            Region::new(alias("1", Some("GRCh38")), Some(99), Some(200))
+        );
+        assert_eq!(
+            "refseq:NM_001304430.2".parse::<Region>()?,
+            Region::new(alias("NM_001304430.2", Some("NCBI")), None, None)
+        );
+        assert_eq!(
+            Region::parse("chr1:0-10", CoordinateSystem::ZeroBasedHalfOpen)?,
+            Region::new(alias("chr1", None), Some(0), Some(10))
+        );
+        assert_eq!(
+            Region::parse("chr1:10-10", CoordinateSystem::ZeroBasedHalfOpen)?,
+            Region::new(alias("chr1", None), Some(10), Some(10))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_invalid() {
+        for s in ["chr1:0-10", "chr1:20-10", ":1-10", "chr1:1-2-3", "chr1:1-,"] {
+            assert!(s.parse::<Region>().is_err(), "{s}");
+        }
+        assert!(Region::parse("chr1:11-10", CoordinateSystem::ZeroBasedHalfOpen).is_err());
+    }
+
+    #[test]
+    fn display() -> Result<(), anyhow::Error> {
+        for s in [
+            "refseq:NM_001304430.2:1-10",
+            "chr1:1000-2000",
+            "chr1:5-",
+            "ga4gh:SQ.5q5HZTCRudL17NTiv5Bn6th__0FrZH04",
+        ] {
+            assert_eq!(s.parse::<Region>()?.to_string(), s);
+        }
+        assert_eq!(
+            Region::new(alias("chr1", None), None, Some(10)).to_string(),
+            "chr1:1-10"
+        );
+        assert_eq!(
+            Region::new(alias("chr1", None), Some(usize::MAX), None).to_string(),
+            format!("chr1:{}-", usize::MAX as u128 + 1)
+        );
+
+        Ok(())
+    }
+}
+
+// <LICENSE>
+// Copyright 2023 seqrepo-rs Contributors
+// Copyright 2016 biocommons.seqrepo Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+// </LICENSE>
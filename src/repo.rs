@@ -1,12 +1,54 @@
 //! Code providing the base `SeqRepo` implementation.
 
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::ops::Range;
 use std::path::{Path, PathBuf};
 
+use crate::digest::{self, SequenceDigests};
 use crate::error::Error;
-use crate::interface::Interface;
-use crate::{AliasDb, AliasOrSeqId, FastaDir, Namespace, Query};
+use crate::interface::{Interface, SequenceInfo};
+use crate::{
+    AliasDb, AliasDbConfig, AliasOrSeqId, AliasVersion, FastaDir, FastaDirConfig, Namespace,
+    NamespacedAlias, Query, ResolveOptions, SequenceReader,
+};
 
-/// Provide (read-only) access to a `seqrepo` sequence repository.
+/// Configuration of a `SeqRepo`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SeqRepoConfig {
+    /// Configuration of the underlying `AliasDb`.
+    pub alias_db: AliasDbConfig,
+    /// Configuration of the underlying `FastaDir`.
+    pub fasta_dir: FastaDirConfig,
+    /// Options for resolving aliases, e.g., in `fetch_sequence()`.
+    pub resolve: ResolveOptions,
+}
+
+/// Summary of loading a FASTA file with `SeqRepo::load_fasta()`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LoadStats {
+    /// Number of records read from the FASTA file.
+    pub n_records: usize,
+    /// Number of sequences that were not in the repository before.
+    pub n_sequences_added: usize,
+}
+
+/// An instance below a seqrepo root directory as returned by `SeqRepo::list_instances()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Instance {
+    /// Name of the instance, e.g., `2024-02-20` or `latest`.
+    pub name: String,
+    /// Path to the instance directory.
+    pub path: PathBuf,
+    /// Target of the instance if it is a symlink, e.g., `2024-02-20` for `latest`.
+    pub symlink_target: Option<PathBuf>,
+}
+
+/// Provide access to a `seqrepo` sequence repository.
+///
+/// Repositories are opened read-only by default.  Use `SeqRepoConfig` to open them
+/// for writing, e.g., for loading sequences with `SeqRepo::load_fasta()`.
 #[derive(Debug)]
 pub struct SeqRepo {
     /// The path to the seqrepo root directory.
@@ -17,27 +59,216 @@ pub struct SeqRepo {
     alias_db: AliasDb,
     /// The `FastaDir` to use.
     fasta_dir: FastaDir,
+    /// Options for resolving aliases.
+    resolve_options: ResolveOptions,
 }
 
 impl SeqRepo {
     /// Create new `SeqRepo` at the given path.
     pub fn new<P>(path: P, instance: &str) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+    {
+        Self::new_with_config(path, instance, Default::default())
+    }
+
+    /// Create new `SeqRepo` at the given path with the given configuration.
+    pub fn new_with_config<P>(path: P, instance: &str, config: SeqRepoConfig) -> Result<Self, Error>
     where
         P: AsRef<Path>,
     {
         let root_dir = PathBuf::from(path.as_ref());
         let instance = instance.to_string();
-        let alias_db = AliasDb::new(&root_dir, &instance)?;
+        let alias_db = AliasDb::new_with_config(&root_dir, &instance, config.alias_db)?;
         let path_fasta_dir = root_dir.join(&instance).join("sequences");
-        let fasta_dir = FastaDir::new(path_fasta_dir)?;
+        let fasta_dir = FastaDir::new_with_config(path_fasta_dir, config.fasta_dir)?;
         Ok(SeqRepo {
             root_dir,
             instance,
             alias_db,
             fasta_dir,
+            resolve_options: config.resolve,
         })
     }
 
+    /// Initialize a new, empty instance at the given path and open it for writing.
+    ///
+    /// This creates the directory layout and the databases in the same way as the
+    /// Python implementation's `seqrepo init` command.  The root directory is created
+    /// if necessary, the instance must not exist yet.
+    pub fn init<P>(path: P, instance: &str) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+    {
+        let instance_dir = path.as_ref().join(instance);
+        if instance_dir.exists() {
+            return Err(Error::SeqRepoInit(format!(
+                "instance directory {} already exists",
+                instance_dir.display()
+            )));
+        }
+        std::fs::create_dir_all(&instance_dir).map_err(|e| Error::SeqRepoInit(e.to_string()))?;
+
+        AliasDb::init(&path, instance)?;
+        FastaDir::init(instance_dir.join("sequences"))?;
+
+        Self::new_with_config(
+            path,
+            instance,
+            SeqRepoConfig {
+                alias_db: AliasDbConfig {
+                    writeable: true,
+                    ..Default::default()
+                },
+                fasta_dir: FastaDirConfig {
+                    writeable: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        )
+    }
+
+    /// List the instances below the root directory at `path`, sorted by name.
+    ///
+    /// Instances are the directories (or symlinks to directories) that contain an
+    /// `aliases.sqlite3` database.
+    pub fn list_instances<P>(path: P) -> Result<Vec<Instance>, Error>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let entries = std::fs::read_dir(path)
+            .map_err(|e| Error::SeqRepoListInstances(format!("{}: {e}", path.display())))?;
+
+        let mut instances = Vec::new();
+        for entry in entries {
+            let entry = entry.map_err(|e| Error::SeqRepoListInstances(e.to_string()))?;
+            let path = entry.path();
+            if !path.join("aliases.sqlite3").exists() {
+                continue;
+            }
+            instances.push(Instance {
+                name: entry.file_name().to_string_lossy().to_string(),
+                symlink_target: std::fs::read_link(&path).ok(),
+                path,
+            });
+        }
+        instances.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(instances)
+    }
+
+    /// Store the sequence `seq` with the given aliases.
+    ///
+    /// The sequence is normalized with `digest::normalize_sequence()` and identified by its
+    /// `sha512t24u` digest, which is returned.  For new sequences, the `MD5`, `SEGUID`,
+    /// `SHA1`, and `VMC` digest aliases are registered as well.  Changes are written on
+    /// `commit()`.
+    pub fn store(&self, seq: &str, aliases: &[NamespacedAlias]) -> Result<String, Error> {
+        self.store_sequence(seq, aliases).map(|(seq_id, _)| seq_id)
+    }
+
+    /// Implementation of `store()`, additionally returns whether the sequence is new.
+    fn store_sequence(
+        &self,
+        seq: &str,
+        aliases: &[NamespacedAlias],
+    ) -> Result<(String, bool), Error> {
+        let seq = digest::normalize_sequence(seq)?;
+        let digests = SequenceDigests::from_normalized(&seq);
+        let seq_id = digests.sha512t24u.clone();
+
+        let is_new = self.fasta_dir.store(&seq_id, &seq)?;
+        if is_new {
+            let vmc_alias = digests.vmc_alias();
+            let digest_aliases = [
+                (Namespace::MD5, digests.md5),
+                (Namespace::SEGUID, digests.seguid),
+                (Namespace::SHA1, digests.sha1),
+                (Namespace::VMC, vmc_alias),
+            ];
+            for (namespace, alias) in digest_aliases {
+                self.alias_db.store_alias(
+                    &seq_id,
+                    NamespacedAlias {
+                        namespace: Namespace::new(namespace),
+                        alias,
+                    },
+                )?;
+            }
+        }
+        for alias in aliases {
+            self.alias_db.store_alias(&seq_id, alias.clone())?;
+        }
+
+        Ok((seq_id, is_new))
+    }
+
+    /// Write all stored sequences and aliases.
+    pub fn commit(&self) -> Result<(), Error> {
+        // Commit sequences first so aliases never refer to missing sequences.
+        self.fasta_dir.commit()?;
+        self.alias_db.commit()?;
+        Ok(())
+    }
+
+    /// Load all sequences from the FASTA file at `path` and commit them.
+    ///
+    /// The record names are registered as aliases in `namespace`, normalized with
+    /// `Namespace::normalize()`; e.g., as in the Python implementation, the namespace
+    /// `refseq` is stored as `NCBI`.
+    pub fn load_fasta<P>(&self, path: P, namespace: &Namespace) -> Result<LoadStats, Error>
+    where
+        P: AsRef<Path>,
+    {
+        let reader = File::open(path.as_ref())
+            .map(BufReader::new)
+            .map_err(|e| Error::SeqRepoFastaOpen(e.to_string()))?;
+        self.load_fasta_from_reader(reader, namespace)
+    }
+
+    /// Load all sequences in FASTA format from `reader` and commit them.
+    ///
+    /// See `load_fasta()` for details.
+    pub fn load_fasta_from_reader<R>(
+        &self,
+        reader: R,
+        namespace: &Namespace,
+    ) -> Result<LoadStats, Error>
+    where
+        R: BufRead,
+    {
+        let namespace = Namespace::normalize(namespace);
+
+        let mut stats = LoadStats::default();
+        let mut reader = noodles::fasta::Reader::new(reader);
+        for record in reader.records() {
+            let record = record.map_err(|e| Error::SeqRepoFastaRead(e.to_string()))?;
+            let alias = std::str::from_utf8(record.name())
+                .map_err(|e| Error::SeqRepoFastaRead(e.to_string()))?
+                .to_string();
+            let seq = std::str::from_utf8(record.sequence().as_ref())
+                .map_err(|e| Error::SeqRepoFastaRead(e.to_string()))?;
+
+            let (_, is_new) = self.store_sequence(
+                seq,
+                &[NamespacedAlias {
+                    namespace: namespace.clone(),
+                    alias,
+                }],
+            )?;
+
+            stats.n_records += 1;
+            if is_new {
+                stats.n_sequences_added += 1;
+            }
+        }
+        self.commit()?;
+
+        Ok(stats)
+    }
+
     /// Provide access to the root directory.
     pub fn root_dir(&self) -> &Path {
         self.root_dir.as_ref()
@@ -57,6 +288,96 @@ impl SeqRepo {
     pub fn fasta_dir(&self) -> &FastaDir {
         &self.fasta_dir
     }
+
+    /// Resolve `alias_or_seq_id` to `seq_id`s with the configured `ResolveOptions`.
+    pub fn resolve(&self, alias_or_seq_id: &AliasOrSeqId) -> Result<Vec<String>, Error> {
+        self.resolve_with(alias_or_seq_id, &self.resolve_options)
+    }
+
+    /// Resolve `alias_or_seq_id` to `seq_id`s with the given `options`.
+    ///
+    /// See `AliasDb::resolve()` for details.  A `seq_id` is returned as it is.
+    pub fn resolve_with(
+        &self,
+        alias_or_seq_id: &AliasOrSeqId,
+        options: &ResolveOptions,
+    ) -> Result<Vec<String>, Error> {
+        match alias_or_seq_id {
+            AliasOrSeqId::Alias { value, namespace } => Ok(self
+                .alias_db
+                .resolve(value, namespace.as_deref(), options)?
+                .into_iter()
+                .map(|record| record.seqid)
+                .collect()),
+            AliasOrSeqId::SeqId(seqid) => Ok(vec![seqid.clone()]),
+        }
+    }
+
+    /// List all current versions of the unversioned `accession`.
+    ///
+    /// See `AliasDb::versions()` for details.
+    pub fn versions(
+        &self,
+        accession: &str,
+        namespace: Option<&str>,
+    ) -> Result<Vec<AliasVersion>, Error> {
+        self.alias_db.versions(accession, namespace)
+    }
+
+    /// Open a streaming reader over the range `begin..end` of a sequence.
+    ///
+    /// See `FastaDir::sequence_reader()` for details.
+    pub fn sequence_reader(
+        &self,
+        alias_or_seq_id: &AliasOrSeqId,
+        begin: Option<usize>,
+        end: Option<usize>,
+    ) -> Result<SequenceReader, Error> {
+        let seq_id = self.resolve_unique(alias_or_seq_id)?;
+        self.fasta_dir.sequence_reader(&seq_id, begin, end)
+    }
+
+    /// Resolve `alias_or_seq_id` to a single `seq_id` for fetching its sequence.
+    fn resolve_unique(&self, alias_or_seq_id: &AliasOrSeqId) -> Result<String, Error> {
+        match alias_or_seq_id {
+            AliasOrSeqId::Alias { value, namespace } => {
+                let mut records =
+                    self.alias_db
+                        .resolve(value, namespace.as_deref(), &self.resolve_options)?;
+                // The `All` policy may yield several candidates but we can only fetch one.
+                if records.len() > 1 {
+                    return Err(Error::AliasDbResolutionAmbiguous(value.clone(), records));
+                }
+                Ok(records.remove(0).seqid)
+            }
+            AliasOrSeqId::SeqId(seqid) => Ok(seqid.clone()),
+        }
+    }
+
+    /// Return all aliases of the sequence that `alias` in `namespace` refers to.
+    ///
+    /// See `AliasDb::translate_alias()` for details.
+    pub fn translate_alias(
+        &self,
+        alias: &str,
+        namespace: Option<&str>,
+        target_namespaces: Option<&[&str]>,
+    ) -> Result<Vec<NamespacedAlias>, Error> {
+        self.alias_db
+            .translate_alias(alias, namespace, target_namespaces)
+    }
+
+    /// Return all `namespace:alias` identifiers of the sequence that `identifier` refers to.
+    ///
+    /// See `AliasDb::translate_identifier()` for details.
+    pub fn translate_identifier(
+        &self,
+        identifier: &str,
+        target_namespaces: Option<&[&str]>,
+    ) -> Result<Vec<String>, Error> {
+        self.alias_db
+            .translate_identifier(identifier, target_namespaces)
+    }
 }
 
 impl Interface for SeqRepo {
@@ -66,39 +387,83 @@ impl Interface for SeqRepo {
         begin: Option<usize>,
         end: Option<usize>,
     ) -> Result<String, Error> {
-        let seq_ids = match alias_or_seq_id {
-            AliasOrSeqId::Alias { value, namespace } => {
-                let query = Query {
-                    namespace: namespace.as_ref().map(|s| Namespace::new(s)),
-                    alias: Some(value.to_string()),
-                    ..Default::default()
-                };
-                let mut seq_ids = Vec::new();
-                self.alias_db
-                    .find(&query, |record| seq_ids.push(record.unwrap().seqid))?;
-
-                if seq_ids.is_empty() {
-                    return Err(Error::AliasDbResolve(value.clone()));
-                } else if seq_ids.len() > 1 {
-                    return Err(Error::AliasDbResolutionAmbiguous(
-                        value.clone(),
-                        format!("{:?}", &seq_ids),
-                    ));
-                }
+        let seq_id = self.resolve_unique(alias_or_seq_id)?;
+        self.fasta_dir.fetch_sequence_part(&seq_id, begin, end)
+    }
+
+    fn fetch_sequence_part_into(
+        &self,
+        alias_or_seq_id: &AliasOrSeqId,
+        begin: Option<usize>,
+        end: Option<usize>,
+        buf: &mut Vec<u8>,
+    ) -> Result<(), Error> {
+        let seq_id = self.resolve_unique(alias_or_seq_id)?;
+        self.fasta_dir
+            .fetch_sequence_part_into(&seq_id, begin, end, buf)
+    }
 
-                seq_ids
+    fn fetch_sequence_parts(
+        &self,
+        requests: &[(AliasOrSeqId, Range<usize>)],
+    ) -> Result<Vec<String>, Error> {
+        // Resolve each alias only once.
+        let mut seq_ids: HashMap<&AliasOrSeqId, String> = HashMap::new();
+        for (alias_or_seq_id, _) in requests {
+            if !seq_ids.contains_key(alias_or_seq_id) {
+                seq_ids.insert(alias_or_seq_id, self.resolve_unique(alias_or_seq_id)?);
             }
-            AliasOrSeqId::SeqId(seqid) => vec![seqid.clone()],
-        };
+        }
+        let resolved: Vec<_> = requests
+            .iter()
+            .map(|(alias_or_seq_id, range)| (seq_ids[alias_or_seq_id].as_str(), range.clone()))
+            .collect();
+
+        self.fasta_dir.fetch_sequence_parts(&resolved)
+    }
+
+    fn sequence_info(&self, alias_or_seq_id: &AliasOrSeqId) -> Result<SequenceInfo, Error> {
+        let seq_id = self.resolve_unique(alias_or_seq_id)?;
+        let seqinfo = self.fasta_dir.fetch_seqinfo(&seq_id)?;
+        let aliases = self
+            .alias_db
+            .find_all(&Query {
+                seqid: Some(seq_id.clone()),
+                ..Default::default()
+            })?
+            .into_iter()
+            .map(|record| NamespacedAlias {
+                namespace: record.namespace,
+                alias: record.alias,
+            })
+            .collect();
 
-        self.fasta_dir.fetch_sequence_part(&seq_ids[0], begin, end)
+        Ok(SequenceInfo {
+            seq_id,
+            len: seqinfo.len,
+            alphabet: Some(seqinfo.alpha),
+            added: Some(seqinfo.added),
+            aliases,
+        })
+    }
+
+    fn find_sequence(&self, seq: &str) -> Result<Option<String>, Error> {
+        let seq_id = self.seq_id_for(seq)?;
+        Ok(self.fasta_dir.contains(&seq_id)?.then_some(seq_id))
     }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::{AliasOrSeqId, Interface, SeqRepo};
+    use crate::{
+        AliasOrSeqId, CoordinateSystem, Instance, Interface, LoadStats, Namespace, NamespacedAlias,
+        OrderBy, Query, Region, ResolutionPolicy, ResolveOptions, SeqRepo, SeqRepoConfig, Strand,
+        TranslateOptions,
+    };
     use anyhow::Error;
+    use pretty_assertions::assert_eq;
+    use std::io::Read;
+    use temp_testdir::TempDir;
 
     #[test]
     fn test_sync() {
@@ -120,6 +485,459 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn init() -> Result<(), Error> {
+        let temp = TempDir::default();
+        let root_dir = temp.as_ref().join("seqrepo");
+
+        let sr = SeqRepo::init(&root_dir, "latest")?;
+        assert_eq!(sr.fasta_dir().schema_version(), 1);
+        let mut count = 0;
+        sr.alias_db().find(&Query::default(), |_| count += 1)?;
+        assert_eq!(count, 0);
+
+        // Opening the instance again must work, initializing it again must not.
+        SeqRepo::new(&root_dir, "latest")?;
+        assert!(SeqRepo::init(&root_dir, "latest").is_err());
+
+        // The migrations must be recorded for the Python implementation.
+        for db_path in ["aliases.sqlite3", "sequences/db.sqlite3"] {
+            let conn = rusqlite::Connection::open(root_dir.join("latest").join(db_path))?;
+            let migrations = conn
+                .prepare("SELECT migration_id FROM _yoyo_migration ORDER BY migration_id")?
+                .query_map([], |row| row.get::<_, String>(0))?
+                .collect::<Result<Vec<_>, _>>()?;
+            assert_eq!(migrations, vec!["0000-base", "0001-initial"]);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn list_instances() -> Result<(), Error> {
+        assert_eq!(
+            SeqRepo::list_instances("tests/data/seqrepo")?,
+            vec![Instance {
+                name: "latest".to_string(),
+                path: "tests/data/seqrepo/latest".into(),
+                symlink_target: None,
+            }]
+        );
+        assert!(SeqRepo::list_instances("tests/data/seqrepo/missing").is_err());
+
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn list_instances_symlink() -> Result<(), Error> {
+        let temp = TempDir::default();
+        let root_dir = temp.as_ref();
+        SeqRepo::init(root_dir, "2021-01-29")?;
+        SeqRepo::init(root_dir, "2024-02-20")?;
+        std::os::unix::fs::symlink("2024-02-20", root_dir.join("latest"))?;
+        std::fs::create_dir(root_dir.join("other"))?;
+        assert_eq!(
+            SeqRepo::list_instances(root_dir)?
+                .into_iter()
+                .map(|instance| (instance.name, instance.symlink_target))
+                .collect::<Vec<_>>(),
+            vec![
+                ("2021-01-29".to_string(), None),
+                ("2024-02-20".to_string(), None),
+                ("latest".to_string(), Some("2024-02-20".into())),
+            ]
+        );
+
+        Ok(())
+    }
+
+    fn namespaced_aliases(sr: &SeqRepo) -> Result<Vec<(String, String)>, Error> {
+        let mut result = Vec::new();
+        sr.alias_db().find(&Query::default(), |record| {
+            let record = record.unwrap();
+            result.push((record.namespace.value, record.alias));
+        })?;
+        Ok(result)
+    }
+
+    #[test]
+    fn load_fasta() -> Result<(), Error> {
+        let temp = TempDir::default();
+        let root_dir = temp.as_ref().join("seqrepo");
+
+        let sr = SeqRepo::init(&root_dir, "latest")?;
+        let stats = sr.load_fasta(
+            "tests/data/seqrepo/NM_001304430.2.fasta",
+            &Namespace::new("refseq"),
+        )?;
+        assert_eq!(
+            stats,
+            LoadStats {
+                n_records: 1,
+                n_sequences_added: 1
+            }
+        );
+        // Loading again does not add the sequence again.
+        let stats = sr.load_fasta(
+            "tests/data/seqrepo/NM_001304430.2.fasta",
+            &Namespace::new("refseq"),
+        )?;
+        assert_eq!(stats.n_sequences_added, 0);
+
+        // Sequences and aliases must be the same as when loaded with the Python implementation.
+        let sr = SeqRepo::new(&root_dir, "latest")?;
+        let expected = SeqRepo::new("tests/data/seqrepo", "latest")?;
+        assert_eq!(namespaced_aliases(&sr)?, namespaced_aliases(&expected)?);
+        let seq_id = "5q5HZTCRudL17NTiv5Bn6th__0FrZH04";
+        let seqinfo = sr.fasta_dir().fetch_seqinfo(seq_id)?;
+        let expected_seqinfo = expected.fasta_dir().fetch_seqinfo(seq_id)?;
+        assert_eq!(seqinfo.len, expected_seqinfo.len);
+        assert_eq!(seqinfo.alpha, expected_seqinfo.alpha);
+        let path = root_dir.join("latest/sequences").join(&seqinfo.relpath);
+        assert_eq!(
+            std::fs::read_to_string(format!("{}.fai", path.display()))?,
+            std::fs::read_to_string(format!(
+                "tests/data/seqrepo/latest/sequences/{}.fai",
+                &expected_seqinfo.relpath
+            ))?
+        );
+        assert_eq!(
+            std::fs::read(format!("{}.gzi", path.display()))?,
+            std::fs::read(format!(
+                "tests/data/seqrepo/latest/sequences/{}.gzi",
+                &expected_seqinfo.relpath
+            ))?
+        );
+        assert_eq!(
+            sr.fetch_sequence(&AliasOrSeqId::Alias {
+                value: "NM_001304430.2".to_string(),
+                namespace: None,
+            })?,
+            expected.fetch_sequence(&AliasOrSeqId::SeqId(seq_id.to_string()))?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn store_reassigns_alias() -> Result<(), Error> {
+        let temp = TempDir::default();
+        let sr = SeqRepo::init(temp.as_ref(), "latest")?;
+
+        let alias = NamespacedAlias {
+            namespace: Namespace::new("NCBI"),
+            alias: "NM_1.1".to_string(),
+        };
+        let first = sr.store("acgt", std::slice::from_ref(&alias))?;
+        sr.commit()?;
+        let second = sr.store("ACGTACGT", &[alias])?;
+        sr.commit()?;
+        assert_ne!(first, second);
+
+        let records = sr
+            .alias_db()
+            .find_all(&Query {
+                alias: Some("NM_1.1".to_string()),
+                current_only: false,
+                order_by: OrderBy::SeqaliasId,
+                ..Default::default()
+            })?
+            .into_iter()
+            .map(|record| (record.seqid, record.is_current))
+            .collect::<Vec<_>>();
+        assert_eq!(records, vec![(first, false), (second, true)]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn resolution_policies() -> Result<(), Error> {
+        let temp = TempDir::default();
+        let sr = SeqRepo::init(temp.as_ref(), "latest")?;
+        let alias = |namespace| NamespacedAlias {
+            namespace: Namespace::new(namespace),
+            alias: "X".to_string(),
+        };
+        let ensembl = sr.store("AAAA", &[alias("Ensembl")])?;
+        let lrg = sr.store("CCCC", &[alias("LRG")])?;
+        sr.commit()?;
+
+        let x = AliasOrSeqId::Alias {
+            value: "X".to_string(),
+            namespace: None,
+        };
+        let resolve = |policy| {
+            sr.resolve_with(
+                &x,
+                &ResolveOptions {
+                    policy,
+                    ..Default::default()
+                },
+            )
+        };
+
+        match resolve(ResolutionPolicy::Error) {
+            Err(crate::Error::AliasDbResolutionAmbiguous(alias, records)) => {
+                assert_eq!(alias, "X");
+                assert_eq!(records.len(), 2);
+            }
+            result => panic!("unexpected result: {result:?}"),
+        }
+        assert!(sr.fetch_sequence(&x).is_err());
+
+        assert_eq!(
+            resolve(ResolutionPolicy::NamespacePriority(vec![
+                "refseq".to_string(),
+                "lrg".to_string()
+            ]))?,
+            vec![lrg.clone()]
+        );
+        assert!(resolve(ResolutionPolicy::NamespacePriority(vec![
+            "refseq".to_string()
+        ]))
+        .is_err());
+        assert_eq!(resolve(ResolutionPolicy::MostRecent)?, vec![lrg.clone()]);
+
+        let mut all = resolve(ResolutionPolicy::All)?;
+        all.sort();
+        let mut expected = vec![ensembl.clone(), lrg];
+        expected.sort();
+        assert_eq!(all, expected);
+
+        let sr = SeqRepo::new_with_config(
+            temp.as_ref(),
+            "latest",
+            SeqRepoConfig {
+                resolve: ResolveOptions {
+                    policy: ResolutionPolicy::NamespacePriority(vec!["Ensembl".to_string()]),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(sr.resolve(&x)?, vec![ensembl]);
+        assert_eq!(sr.fetch_sequence(&x)?, "AAAA");
+
+        Ok(())
+    }
+
+    #[test]
+    fn versionless() -> Result<(), Error> {
+        let temp = TempDir::default();
+        let sr = SeqRepo::init(temp.as_ref(), "latest")?;
+        let alias = |alias: &str| NamespacedAlias {
+            namespace: Namespace::new("NCBI"),
+            alias: alias.to_string(),
+        };
+        sr.store("AAAA", &[alias("NM_1.9")])?;
+        let latest = sr.store("CCCC", &[alias("NM_1.10")])?;
+        sr.store("GGGG", &[alias("NM_10.11")])?;
+        sr.commit()?;
+
+        let versions = sr
+            .versions("NM_1", Some("refseq"))?
+            .into_iter()
+            .map(|version| (version.version, version.record.alias))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            versions,
+            vec![(9, "NM_1.9".to_string()), (10, "NM_1.10".to_string())]
+        );
+
+        let nm_1 = AliasOrSeqId::Alias {
+            value: "NM_1".to_string(),
+            namespace: Some("refseq".to_string()),
+        };
+        assert!(sr.resolve(&nm_1).is_err());
+        let options = ResolveOptions {
+            versionless: true,
+            ..Default::default()
+        };
+        assert_eq!(sr.resolve_with(&nm_1, &options)?, vec![latest]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn store_read_only() -> Result<(), Error> {
+        let sr = SeqRepo::new("tests/data/seqrepo", "latest")?;
+        assert!(sr.store("ACGT", &[]).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn translate_identifier() -> Result<(), Error> {
+        let sr = SeqRepo::new("tests/data/seqrepo", "latest")?;
+
+        assert_eq!(
+            sr.translate_identifier("NM_001304430.2", Some(&["ga4gh", "MD5"]))?,
+            vec![
+                "MD5:a8e7e4cbd2fa521b45b23692b2dd601c",
+                "ga4gh:SQ.5q5HZTCRudL17NTiv5Bn6th__0FrZH04",
+            ]
+        );
+        assert_eq!(
+            sr.fetch_sequence_part(
+                &AliasOrSeqId::Alias {
+                    value: "SQ.5q5HZTCRudL17NTiv5Bn6th__0FrZH04".to_string(),
+                    namespace: Some("ga4gh".to_string()),
+                },
+                Some(0),
+                Some(10)
+            )?,
+            "ACTGCTGAGC"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn fetch_sequence_parts() -> Result<(), Error> {
+        let sr = SeqRepo::new("tests/data/seqrepo", "latest")?;
+        let alias: AliasOrSeqId = "refseq:NM_001304430.2".parse()?;
+        let seq_id: AliasOrSeqId = "ga4gh:SQ.5q5HZTCRudL17NTiv5Bn6th__0FrZH04".parse()?;
+
+        assert_eq!(
+            sr.fetch_sequence_parts(&[(alias.clone(), 100..110), (seq_id, 0..10), (alias, 0..10)])?,
+            vec!["ATGTAGGTAA", "ACTGCTGAGC", "ACTGCTGAGC"]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn sequence_info() -> Result<(), Error> {
+        let sr = SeqRepo::new("tests/data/seqrepo", "latest")?;
+        let info = sr.sequence_info(&"refseq:NM_001304430.2".parse()?)?;
+        assert_eq!(info.seq_id, "5q5HZTCRudL17NTiv5Bn6th__0FrZH04");
+        assert_eq!(info.len, 1873);
+        assert_eq!(info.alphabet.as_deref(), Some("ACGT"));
+        assert_eq!(
+            info.added.map(|added| added.to_string()).as_deref(),
+            Some("2023-02-16 09:46:06")
+        );
+        assert_eq!(
+            info.aliases
+                .iter()
+                .map(|alias| alias.to_string())
+                .collect::<Vec<_>>(),
+            vec![
+                "MD5:a8e7e4cbd2fa521b45b23692b2dd601c",
+                "NCBI:NM_001304430.2",
+                "SEGUID:U5AvKXlRSRwJgn/Zxsa286iO/sg",
+                "SHA1:53902f297951491c09827fd9c6c6b6f3a88efec8",
+                "VMC:GS_5q5HZTCRudL17NTiv5Bn6th__0FrZH04",
+            ]
+        );
+        assert_eq!(
+            sr.sequence_info(&AliasOrSeqId::SeqId(info.seq_id.clone()))?,
+            info
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn fetch_sequence_part_stranded() -> Result<(), Error> {
+        let sr = SeqRepo::new("tests/data/seqrepo", "latest")?;
+        let aos: AliasOrSeqId = "refseq:NM_001304430.2".parse()?;
+
+        assert_eq!(
+            sr.fetch_sequence_part_stranded(&aos, Some(0), Some(10), Strand::Reverse)?,
+            "GCTCAGCAGT"
+        );
+        assert_eq!(
+            sr.fetch_sequence_part_stranded(&aos, Some(0), Some(10), Strand::Forward)?,
+            "ACTGCTGAGC"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn translate_sequence_part() -> Result<(), Error> {
+        let sr = SeqRepo::new("tests/data/seqrepo", "latest")?;
+        let aos: AliasOrSeqId = "refseq:NM_001304430.2".parse()?;
+
+        assert_eq!(
+            sr.translate_sequence_part(&aos, Some(0), Some(14), &Default::default())?,
+            "TAEL"
+        );
+        assert_eq!(
+            sr.translate_sequence_part(
+                &aos,
+                Some(0),
+                Some(14),
+                &TranslateOptions {
+                    frame: 1,
+                    ..Default::default()
+                }
+            )?,
+            "LLSW"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn fetch_region() -> Result<(), Error> {
+        let sr = SeqRepo::new("tests/data/seqrepo", "latest")?;
+
+        assert_eq!(
+            sr.fetch_region(&"refseq:NM_001304430.2:1-10".parse()?)?,
+            "ACTGCTGAGC"
+        );
+        assert_eq!(
+            sr.fetch_region(&Region::parse(
+                "NM_001304430.2:1,869-1,873",
+                CoordinateSystem::ZeroBasedHalfOpen
+            )?)?,
+            "TATA"
+        );
+        assert_eq!(sr.fetch_region(&"NM_001304430.2".parse()?)?.len(), 1873);
+
+        Ok(())
+    }
+
+    #[test]
+    fn sequence_reader() -> Result<(), Error> {
+        let sr = SeqRepo::new("tests/data/seqrepo", "latest")?;
+        let alias = AliasOrSeqId::Alias {
+            value: "NM_001304430.2".to_string(),
+            namespace: None,
+        };
+
+        let mut seq = String::new();
+        sr.sequence_reader(&alias, Some(0), Some(10))?
+            .read_to_string(&mut seq)?;
+        assert_eq!(seq, "ACTGCTGAGC");
+
+        Ok(())
+    }
+
+    #[test]
+    fn find_sequence() -> Result<(), Error> {
+        let sr = SeqRepo::new("tests/data/seqrepo", "latest")?;
+        let seq = sr.fetch_sequence(&AliasOrSeqId::Alias {
+            value: "NM_001304430.2".to_string(),
+            namespace: None,
+        })?;
+
+        assert_eq!(sr.seq_id_for(&seq)?, "5q5HZTCRudL17NTiv5Bn6th__0FrZH04");
+        assert_eq!(
+            sr.find_sequence(&seq.to_lowercase())?,
+            Some("5q5HZTCRudL17NTiv5Bn6th__0FrZH04".to_string())
+        );
+        assert!(sr.contains_sequence(&seq)?);
+        assert_eq!(sr.find_sequence("ACGT")?, None);
+        assert!(!sr.contains_sequence("ACGT")?);
+        assert!(sr.find_sequence("ACGT-").is_err());
+
+        Ok(())
+    }
+
     #[test]
     fn fetch_sequence() -> Result<(), Error> {
         let sr = SeqRepo::new("tests/data/seqrepo", "latest")?;
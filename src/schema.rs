@@ -0,0 +1,130 @@
+//! Creation of the SQLite databases of a `seqrepo` instance.
+//!
+//! The Python reference implementation manages its schemas with `yoyo` migrations.
+//! We create the same tables and record the migrations as applied so the Python
+//! implementation can open (and write to) the databases that we create.
+
+use std::path::Path;
+
+use rusqlite::Connection;
+
+/// Tables used by `yoyo` for tracking the applied migrations.
+static YOYO_SCHEMA: &str = r#"
+CREATE TABLE IF NOT EXISTS "yoyo_lock" (locked INT DEFAULT 1, ctime TIMESTAMP,pid INT NOT NULL,PRIMARY KEY (locked));
+CREATE TABLE IF NOT EXISTS "_yoyo_log" ( id VARCHAR(36), migration_hash VARCHAR(64), migration_id VARCHAR(255), operation VARCHAR(10), username VARCHAR(255), hostname VARCHAR(255), comment VARCHAR(255), created_at_utc TIMESTAMP, PRIMARY KEY (id));
+CREATE TABLE IF NOT EXISTS "_yoyo_version" (version INT NOT NULL PRIMARY KEY, installed_at_utc TIMESTAMP);
+CREATE TABLE IF NOT EXISTS "_yoyo_migration" ( migration_hash VARCHAR(64), migration_id VARCHAR(255), applied_at_utc TIMESTAMP, PRIMARY KEY (migration_hash));
+"#;
+
+/// Version of the `yoyo` bookkeeping tables.
+static YOYO_VERSION: u32 = 2;
+
+/// The migrations applied by the Python implementation, as `(hash, id)`.
+static YOYO_MIGRATIONS: &[(&str, &str)] = &[
+    (
+        "00655420539ecc1c759418763d9667111f42a8e3be180b1aad57c843f3347b12",
+        "0000-base",
+    ),
+    (
+        "f8307527fff5cfa486d1b117e221aaaee936c1a14266e74466a98ab557500f04",
+        "0001-initial",
+    ),
+];
+
+/// Schema of the `aliases.sqlite3` database.
+pub(crate) static ALIASES_SCHEMA: &str = r#"
+CREATE TABLE meta (key text not null, value text not null);
+CREATE UNIQUE INDEX meta_key_idx on meta(key);
+CREATE TABLE log (ts timestamp not null default current_timestamp, v text not null, msg text not null);
+CREATE TABLE seqalias (
+    seqalias_id integer primary key,
+    seq_id text not null,
+    namespace text not null,
+    alias text not null,
+    added timestamp not null default current_timestamp,
+    is_current int not null default 1
+);
+CREATE UNIQUE INDEX seqalias_unique_ns_alias_idx on seqalias(namespace, alias) where is_current = 1
+;
+CREATE INDEX seqalias_seq_id_idx on seqalias(seq_id)
+;
+CREATE INDEX seqalias_namespace_idx on seqalias(namespace)
+;
+CREATE INDEX seqalias_alias_idx on seqalias(alias)
+;
+"#;
+
+/// Schema of the `sequences/db.sqlite3` database.
+pub(crate) static SEQUENCES_SCHEMA: &str = r#"
+CREATE TABLE meta (key text not null, value text not null);
+CREATE UNIQUE INDEX meta_key_idx on meta(key);
+CREATE TABLE log (ts timestamp not null default current_timestamp, v text not null, msg text not null);
+CREATE TABLE seqinfo (
+    seq_id text primary key,
+    len integer not null,
+    alpha text not null,
+    added timestamp not null default current_timestamp,
+    relpath text not null
+);
+CREATE UNIQUE INDEX seqinfo_seq_id_idx on seqinfo(seq_id);
+"#;
+
+/// Create a new database at `path` with the given `schema` and schema version.
+///
+/// Fails if the database file already exists.
+pub(crate) fn create_database(
+    path: &Path,
+    schema: &str,
+    schema_version: u32,
+) -> Result<(), rusqlite::Error> {
+    if path.exists() {
+        return Err(rusqlite::Error::InvalidPath(path.to_path_buf()));
+    }
+
+    let mut conn = Connection::open(path)?;
+    let now = chrono::Utc::now()
+        .naive_utc()
+        .format("%Y-%m-%d %H:%M:%S%.6f")
+        .to_string();
+
+    let tx = conn.transaction()?;
+    tx.execute_batch(YOYO_SCHEMA)?;
+    tx.execute(
+        "INSERT INTO _yoyo_version (version, installed_at_utc) VALUES (?, ?)",
+        (YOYO_VERSION, &now),
+    )?;
+    tx.execute_batch(schema)?;
+    for (migration_hash, migration_id) in YOYO_MIGRATIONS {
+        tx.execute(
+            "INSERT INTO _yoyo_migration (migration_hash, migration_id, applied_at_utc) \
+            VALUES (?, ?, ?)",
+            (migration_hash, migration_id, &now),
+        )?;
+    }
+    tx.execute(
+        "INSERT INTO meta (key, value) VALUES ('schema version', ?)",
+        [schema_version.to_string()],
+    )?;
+    tx.execute(
+        "INSERT INTO log (v, msg) VALUES (?, 'database created')",
+        [env!("CARGO_PKG_VERSION")],
+    )?;
+    tx.commit()
+}
+
+// <LICENSE>
+// Copyright 2023 seqrepo-rs Contributors
+// Copyright 2016 biocommons.seqrepo Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+// </LICENSE>
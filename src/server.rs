@@ -0,0 +1,478 @@
+//! HTTP server exposing an `Interface` implementation via the GA4GH refget v2 API.
+//!
+//! The following endpoints are provided, see the [refget specification][spec]:
+//!
+//! - `GET /sequence/service-info`
+//! - `GET /sequence/{id}` with optional `start`/`end` query parameters or `Range` header
+//! - `GET /sequence/{id}/metadata`
+//!
+//! Identifiers are resolved through the repository, so besides `ga4gh:SQ.<seq_id>`,
+//! `SQ.<seq_id>` and `md5:<digest>` (or plain MD5 digests), aliases such as RefSeq
+//! accessions may be used.
+//!
+//! [spec]: https://samtools.github.io/hts-specs/refget.html
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use axum::extract::{Path, Query, State};
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::Router;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::aliases::GA4GH_PREFIX;
+use crate::async_interface::{AsyncInterface, AsyncSeqRepo};
+use crate::digest;
+use crate::error::Error;
+use crate::interface::{AliasOrSeqId, Interface};
+use crate::Namespace;
+
+/// Content type of sequence responses.
+static CONTENT_TYPE_SEQUENCE: &str = "text/vnd.ga4gh.refget.v2.0.0+plain; charset=us-ascii";
+/// Content type of metadata responses.
+static CONTENT_TYPE_JSON: &str = "application/vnd.ga4gh.refget.v2.0.0+json";
+
+/// Configuration of the refget server, used for the `service-info` endpoint.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServerConfig {
+    /// Identifier of the service in reverse domain name format.
+    pub id: String,
+    /// Human-readable name of the service.
+    pub name: String,
+    /// Name of the organization running the service.
+    pub organization_name: String,
+    /// URL of the organization running the service.
+    pub organization_url: String,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            id: "org.varfish.seqrepo-rs".to_string(),
+            name: "seqrepo-rs refget server".to_string(),
+            organization_name: "varfish-org".to_string(),
+            organization_url: "https://github.com/varfish-org/seqrepo-rs".to_string(),
+        }
+    }
+}
+
+/// Shared state of the request handlers.
+struct ServerState<T> {
+    repo: AsyncSeqRepo<T>,
+    config: ServerConfig,
+}
+
+/// Build the refget `Router` serving the sequences of `repo`.
+pub fn router<T>(repo: Arc<T>, config: ServerConfig) -> Router
+where
+    T: Interface + Send + Sync + 'static,
+{
+    let state = Arc::new(ServerState {
+        repo: AsyncSeqRepo::from_arc(repo),
+        config,
+    });
+    Router::new()
+        .route("/sequence/service-info", get(service_info::<T>))
+        .route("/sequence/{id}", get(sequence::<T>))
+        .route("/sequence/{id}/metadata", get(metadata::<T>))
+        .with_state(state)
+}
+
+/// Serve the sequences of `repo` on `listener` until the server fails.
+pub async fn serve<T>(
+    repo: Arc<T>,
+    config: ServerConfig,
+    listener: tokio::net::TcpListener,
+) -> Result<(), Error>
+where
+    T: Interface + Send + Sync + 'static,
+{
+    axum::serve(listener, router(repo, config))
+        .await
+        .map_err(|e| Error::Server(e.to_string()))
+}
+
+/// Error response with status code and plain text message.
+#[derive(Debug)]
+struct ErrorResponse(StatusCode, String);
+
+impl IntoResponse for ErrorResponse {
+    fn into_response(self) -> Response {
+        (self.0, self.1).into_response()
+    }
+}
+
+impl From<Error> for ErrorResponse {
+    fn from(e: Error) -> Self {
+        let status = match &e {
+            e if e.is_not_found() => StatusCode::NOT_FOUND,
+            Error::InvalidIdentifier(_) => StatusCode::NOT_FOUND,
+            Error::AliasDbResolutionAmbiguous(..) => StatusCode::BAD_REQUEST,
+            Error::InvalidRange(..) | Error::RangeOutOfBounds(..) => {
+                StatusCode::RANGE_NOT_SATISFIABLE
+            }
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        Self(status, e.to_string())
+    }
+}
+
+/// Map the refget identifier `id` to the sequence to look up.
+///
+/// `SQ.<seq_id>` and plain MD5 digests are accepted as used by refget, everything
+/// else is parsed as `AliasOrSeqId`.
+fn parse_identifier(id: &str) -> Result<AliasOrSeqId, Error> {
+    if let Some(seq_id) = id.strip_prefix(GA4GH_PREFIX) {
+        Ok(AliasOrSeqId::SeqId(seq_id.to_string()))
+    } else if id.len() == 32 && id.bytes().all(|b| b.is_ascii_hexdigit()) {
+        Ok(AliasOrSeqId::Alias {
+            value: id.to_ascii_lowercase(),
+            namespace: Some(Namespace::MD5.to_string()),
+        })
+    } else {
+        id.parse()
+    }
+}
+
+/// Parse the `Range` header value `bytes=<first>-[<last>]` into a half-open range.
+fn parse_range_header(value: &HeaderValue) -> Result<(usize, Option<usize>), ErrorResponse> {
+    let invalid = || {
+        ErrorResponse(
+            StatusCode::BAD_REQUEST,
+            format!("invalid Range header: {value:?}"),
+        )
+    };
+    let range = value
+        .to_str()
+        .ok()
+        .and_then(|value| value.trim().strip_prefix("bytes="))
+        .ok_or_else(invalid)?;
+    let (first, last) = range.split_once('-').ok_or_else(invalid)?;
+    let first = first.trim().parse::<usize>().map_err(|_| invalid())?;
+    let last = match last.trim() {
+        "" => None,
+        last => Some(last.parse::<usize>().map_err(|_| invalid())?),
+    };
+    let not_satisfiable = || {
+        ErrorResponse(
+            StatusCode::RANGE_NOT_SATISFIABLE,
+            format!("invalid Range header: {value:?}"),
+        )
+    };
+    match last {
+        Some(last) if last < first => Err(not_satisfiable()),
+        Some(last) => Ok((
+            first,
+            Some(last.checked_add(1).ok_or_else(not_satisfiable)?),
+        )),
+        None => Ok((first, None)),
+    }
+}
+
+/// Query parameters of the sequence endpoint.
+#[derive(Debug, Deserialize)]
+struct SequenceParams {
+    start: Option<usize>,
+    end: Option<usize>,
+}
+
+/// Handler of `GET /sequence/{id}`.
+async fn sequence<T>(
+    State(state): State<Arc<ServerState<T>>>,
+    Path(id): Path<String>,
+    Query(params): Query<SequenceParams>,
+    headers: HeaderMap,
+) -> Result<Response, ErrorResponse>
+where
+    T: Interface + Send + Sync + 'static,
+{
+    let alias_or_seq_id = parse_identifier(&id)?;
+    let (begin, end, status) = match headers.get(header::RANGE) {
+        Some(_) if params.start.is_some() || params.end.is_some() => {
+            return Err(ErrorResponse(
+                StatusCode::BAD_REQUEST,
+                "cannot combine Range header with start/end parameters".to_string(),
+            ));
+        }
+        Some(value) => {
+            let (begin, end) = parse_range_header(value)?;
+            (Some(begin), end, StatusCode::PARTIAL_CONTENT)
+        }
+        None => (params.start, params.end, StatusCode::OK),
+    };
+    if let (Some(begin), Some(end)) = (begin, end) {
+        if begin > end {
+            return Err(ErrorResponse(
+                StatusCode::NOT_IMPLEMENTED,
+                "circular sequences are not supported".to_string(),
+            ));
+        }
+    }
+
+    let seq = state
+        .repo
+        .fetch_sequence_part(&alias_or_seq_id, begin, end)
+        .await?;
+    Ok((status, [(header::CONTENT_TYPE, CONTENT_TYPE_SEQUENCE)], seq).into_response())
+}
+
+/// Handler of `GET /sequence/{id}/metadata`.
+async fn metadata<T>(
+    State(state): State<Arc<ServerState<T>>>,
+    Path(id): Path<String>,
+) -> Result<Response, ErrorResponse>
+where
+    T: Interface + Send + Sync + 'static,
+{
+    let info = state.repo.sequence_info(&parse_identifier(&id)?).await?;
+    let md5 = match info
+        .aliases
+        .iter()
+        .find(|alias| alias.namespace.value == Namespace::MD5)
+    {
+        Some(alias) => alias.alias.clone(),
+        None => {
+            let seq_id = AliasOrSeqId::SeqId(info.seq_id.clone());
+            digest::seq_md5(&state.repo.fetch_sequence(&seq_id).await?)?
+        }
+    };
+    let aliases = info
+        .aliases
+        .iter()
+        .map(|alias| {
+            HashMap::from([
+                ("alias", alias.alias.as_str()),
+                ("naming_authority", alias.namespace.canonical_name()),
+            ])
+        })
+        .collect::<Vec<_>>();
+
+    let body = json!({
+        "metadata": {
+            "id": format!("{GA4GH_PREFIX}{}", info.seq_id),
+            "md5": md5,
+            "ga4gh": format!("{GA4GH_PREFIX}{}", info.seq_id),
+            "length": info.len,
+            "aliases": aliases,
+        }
+    });
+    Ok((
+        [(header::CONTENT_TYPE, CONTENT_TYPE_JSON)],
+        body.to_string(),
+    )
+        .into_response())
+}
+
+/// Handler of `GET /sequence/service-info`.
+async fn service_info<T>(State(state): State<Arc<ServerState<T>>>) -> Response
+where
+    T: Interface + Send + Sync + 'static,
+{
+    let config = &state.config;
+    let body = json!({
+        "id": config.id,
+        "name": config.name,
+        "type": {
+            "group": "org.ga4gh",
+            "artifact": "refget",
+            "version": "2.0.0",
+        },
+        "organization": {
+            "name": config.organization_name,
+            "url": config.organization_url,
+        },
+        "version": env!("CARGO_PKG_VERSION"),
+        "refget": {
+            "circular_supported": false,
+            "algorithms": ["md5", "ga4gh"],
+            "identifier_types": ["ga4gh", "md5", "refseq", "ensembl"],
+            "subsequence_limit": null,
+        },
+    });
+    (
+        [(header::CONTENT_TYPE, "application/json")],
+        body.to_string(),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod test {
+    use std::net::SocketAddr;
+    use std::sync::Arc;
+
+    use pretty_assertions::assert_eq;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::{TcpListener, TcpStream};
+
+    use super::{serve, ServerConfig};
+    use crate::SeqRepo;
+
+    /// Start a server on a free port on localhost.
+    async fn start() -> Result<SocketAddr, anyhow::Error> {
+        let repo = Arc::new(SeqRepo::new("tests/data/seqrepo", "latest")?);
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+        tokio::spawn(serve(repo, ServerConfig::default(), listener));
+        Ok(addr)
+    }
+
+    /// Send `GET path` with the given headers, return status, headers, and body.
+    async fn get(
+        addr: SocketAddr,
+        path: &str,
+        headers: &[&str],
+    ) -> Result<(u16, String, String), anyhow::Error> {
+        let mut stream = TcpStream::connect(addr).await?;
+        let mut request =
+            format!("GET {path} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n");
+        for header in headers {
+            request.push_str(&format!("{header}\r\n"));
+        }
+        request.push_str("\r\n");
+        stream.write_all(request.as_bytes()).await?;
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await?;
+        let (head, body) = response.split_once("\r\n\r\n").unwrap_or((&response, ""));
+        let status = head.split(' ').nth(1).unwrap_or_default().parse()?;
+        Ok((status, head.to_ascii_lowercase(), body.to_string()))
+    }
+
+    #[tokio::test]
+    async fn sequence() -> Result<(), anyhow::Error> {
+        let addr = start().await?;
+
+        for id in [
+            "SQ.5q5HZTCRudL17NTiv5Bn6th__0FrZH04",
+            "ga4gh:SQ.5q5HZTCRudL17NTiv5Bn6th__0FrZH04",
+            "a8e7e4cbd2fa521b45b23692b2dd601c",
+            "md5:a8e7e4cbd2fa521b45b23692b2dd601c",
+            "NM_001304430.2",
+            "refseq:NM_001304430.2",
+        ] {
+            let (status, head, body) =
+                get(addr, &format!("/sequence/{id}?start=0&end=10"), &[]).await?;
+            assert_eq!((status, body.as_str()), (200, "ACTGCTGAGC"), "{id}");
+            assert!(head.contains("content-type: text/vnd.ga4gh.refget.v2.0.0+plain"));
+        }
+
+        let (status, _, body) = get(addr, "/sequence/NM_001304430.2", &[]).await?;
+        assert_eq!((status, body.len()), (200, 1873));
+        let (status, _, body) = get(addr, "/sequence/NM_001304430.2?start=1870", &[]).await?;
+        assert_eq!((status, body.len()), (200, 3));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn sequence_range_header() -> Result<(), anyhow::Error> {
+        let addr = start().await?;
+
+        let (status, _, body) =
+            get(addr, "/sequence/NM_001304430.2", &["Range: bytes=0-9"]).await?;
+        assert_eq!((status, body.as_str()), (206, "ACTGCTGAGC"));
+        let (status, _, body) =
+            get(addr, "/sequence/NM_001304430.2", &["Range: bytes=1870-"]).await?;
+        assert_eq!((status, body.len()), (206, 3));
+
+        let (status, _, _) = get(
+            addr,
+            "/sequence/NM_001304430.2?start=0",
+            &["Range: bytes=0-9"],
+        )
+        .await?;
+        assert_eq!(status, 400);
+        let (status, _, _) = get(addr, "/sequence/NM_001304430.2", &["Range: lines=0-9"]).await?;
+        assert_eq!(status, 400);
+        let (status, _, _) = get(addr, "/sequence/NM_001304430.2", &["Range: bytes=9-0"]).await?;
+        assert_eq!(status, 416);
+        let (status, _, _) = get(
+            addr,
+            "/sequence/NM_001304430.2",
+            &["Range: bytes=0-18446744073709551615"],
+        )
+        .await?;
+        assert_eq!(status, 416);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn sequence_errors() -> Result<(), anyhow::Error> {
+        let addr = start().await?;
+
+        for (path, expected) in [
+            ("/sequence/NM_000000.0", 404),
+            ("/sequence/SQ.xyz", 404),
+            ("/sequence/NM_001304430.2?start=10&end=5", 501),
+            ("/sequence/NM_001304430.2?start=0&end=2000", 416),
+            ("/sequence/NM_001304430.2?start=x", 400),
+        ] {
+            let (status, _, _) = get(addr, path, &[]).await?;
+            assert_eq!(status, expected, "{path}");
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn metadata() -> Result<(), anyhow::Error> {
+        let addr = start().await?;
+
+        let (status, head, body) = get(addr, "/sequence/NM_001304430.2/metadata", &[]).await?;
+        assert_eq!(status, 200);
+        assert!(head.contains("content-type: application/vnd.ga4gh.refget.v2.0.0+json"));
+        let body: serde_json::Value = serde_json::from_str(&body)?;
+        let metadata = &body["metadata"];
+        assert_eq!(metadata["md5"], "a8e7e4cbd2fa521b45b23692b2dd601c");
+        assert_eq!(metadata["ga4gh"], "SQ.5q5HZTCRudL17NTiv5Bn6th__0FrZH04");
+        assert_eq!(metadata["length"], 1873);
+        assert!(metadata["aliases"]
+            .as_array()
+            .unwrap()
+            .contains(&serde_json::json!({
+                "alias": "NM_001304430.2",
+                "naming_authority": "refseq",
+            })));
+
+        let (status, _, _) = get(addr, "/sequence/NM_000000.0/metadata", &[]).await?;
+        assert_eq!(status, 404);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn service_info() -> Result<(), anyhow::Error> {
+        let addr = start().await?;
+
+        let (status, _, body) = get(addr, "/sequence/service-info", &[]).await?;
+        assert_eq!(status, 200);
+        let body: serde_json::Value = serde_json::from_str(&body)?;
+        assert_eq!(body["id"], "org.varfish.seqrepo-rs");
+        assert_eq!(body["type"]["artifact"], "refget");
+        assert_eq!(body["refget"]["circular_supported"], false);
+
+        Ok(())
+    }
+}
+
+// <LICENSE>
+// Copyright 2023 seqrepo-rs Contributors
+// Copyright 2016 biocommons.seqrepo Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+// </LICENSE>
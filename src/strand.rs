@@ -0,0 +1,113 @@
+//! Strands and reverse complements of nucleotide sequences.
+
+/// Strand of a nucleotide sequence, relative to the stored sequence.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Strand {
+    /// The stored sequence.
+    #[default]
+    Forward,
+    /// The reverse complement of the stored sequence.
+    Reverse,
+}
+
+/// Formats as `+` or `-`.
+impl std::fmt::Display for Strand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Strand::Forward => write!(f, "+"),
+            Strand::Reverse => write!(f, "-"),
+        }
+    }
+}
+
+impl Strand {
+    /// Return `seq` on this strand, i.e., reverse complement it for `Reverse`.
+    pub fn apply(&self, seq: String) -> String {
+        match self {
+            Strand::Forward => seq,
+            Strand::Reverse => reverse_complement(&seq),
+        }
+    }
+}
+
+/// Complement of the nucleotide `base`, including IUPAC ambiguity codes.
+///
+/// The case is kept, `U` is complemented to `A`.  Other characters, e.g., gaps,
+/// are returned as they are.
+pub fn complement(base: u8) -> u8 {
+    let complement = match base.to_ascii_uppercase() {
+        b'A' => b'T',
+        b'C' => b'G',
+        b'G' => b'C',
+        b'T' | b'U' => b'A',
+        b'R' => b'Y',
+        b'Y' => b'R',
+        b'S' => b'S',
+        b'W' => b'W',
+        b'K' => b'M',
+        b'M' => b'K',
+        b'B' => b'V',
+        b'V' => b'B',
+        b'D' => b'H',
+        b'H' => b'D',
+        b'N' => b'N',
+        _ => return base,
+    };
+    if base.is_ascii_lowercase() {
+        complement.to_ascii_lowercase()
+    } else {
+        complement
+    }
+}
+
+/// Return the reverse complement of the nucleotide sequence `seq`.
+pub fn reverse_complement(seq: &str) -> String {
+    seq.bytes()
+        .rev()
+        .map(|base| complement(base) as char)
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::{complement, reverse_complement, Strand};
+
+    #[test]
+    fn reverse_complement_iupac() {
+        assert_eq!(reverse_complement("ACGT"), "ACGT");
+        assert_eq!(reverse_complement("AACCGGTTU"), "AAACCGGTT");
+        assert_eq!(reverse_complement("RYSWKMBVDHN"), "NDHBVKMWSRY");
+        assert_eq!(reverse_complement("acgtN-n"), "n-Nacgt");
+        assert_eq!(reverse_complement("ACgt.*"), "*.acGT");
+        assert_eq!(reverse_complement(""), "");
+
+        assert_eq!(complement(b'u'), b'a');
+    }
+
+    #[test]
+    fn strand() {
+        assert_eq!(Strand::default(), Strand::Forward);
+        assert_eq!(Strand::Forward.apply("GATTACA".to_string()), "GATTACA");
+        assert_eq!(Strand::Reverse.apply("GATTACA".to_string()), "TGTAATC");
+        assert_eq!(format!("{}{}", Strand::Forward, Strand::Reverse), "+-");
+    }
+}
+
+// <LICENSE>
+// Copyright 2023 seqrepo-rs Contributors
+// Copyright 2016 biocommons.seqrepo Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+// </LICENSE>
@@ -0,0 +1,479 @@
+//! Translation of nucleotide sequences to protein sequences.
+//!
+//! The genetic codes are the translation tables of the NCBI, see
+//! <https://www.ncbi.nlm.nih.gov/Taxonomy/Utils/wprintgc.cgi>.
+
+use crate::error::Error;
+
+/// A genetic code, i.e., a table for translating codons to amino acids.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GeneticCode {
+    /// The NCBI translation table ID.
+    id: u8,
+    /// The NCBI name of the genetic code.
+    name: &'static str,
+    /// Amino acids of the 64 codons, with bases ordered `TCAG`.
+    amino_acids: &'static str,
+    /// Start codons, marked with `M` in the same order as `amino_acids`.
+    starts: &'static str,
+}
+
+/// The genetic codes known to the NCBI, ordered by ID.
+static GENETIC_CODES: &[GeneticCode] = &[
+    GeneticCode::STANDARD,
+    GeneticCode::VERTEBRATE_MITOCHONDRIAL,
+    GeneticCode {
+        id: 3,
+        name: "Yeast Mitochondrial",
+        amino_acids: "FFLLSSSSYY**CCWWTTTTPPPPHHQQRRRRIIMMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        starts: "----------**----------------------MM---------------M------------",
+    },
+    GeneticCode {
+        id: 4,
+        name: "Mold, Protozoan, and Coelenterate Mitochondrial and Mycoplasma/Spiroplasma",
+        amino_acids: "FFLLSSSSYY**CCWWLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        starts: "--MM------**-------M------------MMMM---------------M------------",
+    },
+    GeneticCode {
+        id: 5,
+        name: "Invertebrate Mitochondrial",
+        amino_acids: "FFLLSSSSYY**CCWWLLLLPPPPHHQQRRRRIIMMTTTTNNKKSSSSVVVVAAAADDEEGGGG",
+        starts: "---M------**--------------------MMMM---------------M------------",
+    },
+    GeneticCode {
+        id: 6,
+        name: "Ciliate, Dasycladacean and Hexamita Nuclear",
+        amino_acids: "FFLLSSSSYYQQCC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        starts: "--------------*--------------------M----------------------------",
+    },
+    GeneticCode {
+        id: 9,
+        name: "Echinoderm and Flatworm Mitochondrial",
+        amino_acids: "FFLLSSSSYY**CCWWLLLLPPPPHHQQRRRRIIIMTTTTNNNKSSSSVVVVAAAADDEEGGGG",
+        starts: "----------**-----------------------M---------------M------------",
+    },
+    GeneticCode {
+        id: 10,
+        name: "Euplotid Nuclear",
+        amino_acids: "FFLLSSSSYY**CCCWLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        starts: "----------**-----------------------M----------------------------",
+    },
+    GeneticCode {
+        id: 11,
+        name: "Bacterial, Archaeal and Plant Plastid",
+        amino_acids: "FFLLSSSSYY**CC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        starts: "---M------**--*----M------------MMMM---------------M------------",
+    },
+    GeneticCode {
+        id: 12,
+        name: "Alternative Yeast Nuclear",
+        amino_acids: "FFLLSSSSYY**CC*WLLLSPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        starts: "----------**--*----M---------------M----------------------------",
+    },
+    GeneticCode {
+        id: 13,
+        name: "Ascidian Mitochondrial",
+        amino_acids: "FFLLSSSSYY**CCWWLLLLPPPPHHQQRRRRIIMMTTTTNNKKSSGGVVVVAAAADDEEGGGG",
+        starts: "---M------**----------------------MM---------------M------------",
+    },
+    GeneticCode {
+        id: 14,
+        name: "Alternative Flatworm Mitochondrial",
+        amino_acids: "FFLLSSSSYYY*CCWWLLLLPPPPHHQQRRRRIIIMTTTTNNNKSSSSVVVVAAAADDEEGGGG",
+        starts: "-----------*-----------------------M----------------------------",
+    },
+    GeneticCode {
+        id: 16,
+        name: "Chlorophycean Mitochondrial",
+        amino_acids: "FFLLSSSSYY*LCC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        starts: "----------*---*--------------------M----------------------------",
+    },
+    GeneticCode {
+        id: 21,
+        name: "Trematode Mitochondrial",
+        amino_acids: "FFLLSSSSYY**CCWWLLLLPPPPHHQQRRRRIIMMTTTTNNNKSSSSVVVVAAAADDEEGGGG",
+        starts: "----------**-----------------------M---------------M------------",
+    },
+    GeneticCode {
+        id: 22,
+        name: "Scenedesmus obliquus Mitochondrial",
+        amino_acids: "FFLLSS*SYY*LCC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        starts: "------*---*---*--------------------M----------------------------",
+    },
+    GeneticCode {
+        id: 23,
+        name: "Thraustochytrium Mitochondrial",
+        amino_acids: "FF*LSSSSYY**CC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        starts: "--*-------**--*-----------------M--M---------------M------------",
+    },
+    GeneticCode {
+        id: 24,
+        name: "Rhabdopleuridae Mitochondrial",
+        amino_acids: "FFLLSSSSYY**CCWWLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSSKVVVVAAAADDEEGGGG",
+        starts: "---M------**-------M---------------M---------------M------------",
+    },
+    GeneticCode {
+        id: 25,
+        name: "Candidate Division SR1 and Gracilibacteria",
+        amino_acids: "FFLLSSSSYY**CCGWLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        starts: "---M------**-----------------------M---------------M------------",
+    },
+    GeneticCode {
+        id: 26,
+        name: "Pachysolen tannophilus Nuclear",
+        amino_acids: "FFLLSSSSYY**CC*WLLLAPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        starts: "----------**--*----M---------------M----------------------------",
+    },
+    GeneticCode {
+        id: 27,
+        name: "Karyorelict Nuclear",
+        amino_acids: "FFLLSSSSYYQQCCWWLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        starts: "--------------*--------------------M----------------------------",
+    },
+    GeneticCode {
+        id: 28,
+        name: "Condylostoma Nuclear",
+        amino_acids: "FFLLSSSSYYQQCCWWLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        starts: "----------**--*--------------------M----------------------------",
+    },
+    GeneticCode {
+        id: 29,
+        name: "Mesodinium Nuclear",
+        amino_acids: "FFLLSSSSYYYYCC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        starts: "--------------*--------------------M----------------------------",
+    },
+    GeneticCode {
+        id: 30,
+        name: "Peritrich Nuclear",
+        amino_acids: "FFLLSSSSYYEECC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        starts: "--------------*--------------------M----------------------------",
+    },
+    GeneticCode {
+        id: 31,
+        name: "Blastocrithidia Nuclear",
+        amino_acids: "FFLLSSSSYYEECCWWLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        starts: "----------**-----------------------M----------------------------",
+    },
+    GeneticCode {
+        id: 33,
+        name: "Cephalodiscidae Mitochondrial UAA-Tyr",
+        amino_acids: "FFLLSSSSYYY*CCWWLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSSKVVVVAAAADDEEGGGG",
+        starts: "---M-------*-------M---------------M---------------M------------",
+    },
+];
+
+impl GeneticCode {
+    /// The standard code, NCBI table 1.
+    pub const STANDARD: GeneticCode = GeneticCode {
+        id: 1,
+        name: "Standard",
+        amino_acids: "FFLLSSSSYY**CC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        starts: "---M------**--*----M---------------M----------------------------",
+    };
+
+    /// The vertebrate mitochondrial code, NCBI table 2.
+    pub const VERTEBRATE_MITOCHONDRIAL: GeneticCode = GeneticCode {
+        id: 2,
+        name: "Vertebrate Mitochondrial",
+        amino_acids: "FFLLSSSSYY**CCWWLLLLPPPPHHQQRRRRIIMMTTTTNNKKSS**VVVVAAAADDEEGGGG",
+        starts: "----------**--------------------MMMM----------**---M------------",
+    };
+
+    /// Return the genetic code with the NCBI translation table `id`.
+    pub fn from_id(id: u8) -> Result<Self, Error> {
+        GENETIC_CODES
+            .iter()
+            .find(|code| code.id == id)
+            .copied()
+            .ok_or(Error::UnknownGeneticCode(id))
+    }
+
+    /// All known genetic codes, ordered by ID.
+    pub fn all() -> &'static [GeneticCode] {
+        GENETIC_CODES
+    }
+
+    /// The NCBI translation table ID.
+    pub fn id(&self) -> u8 {
+        self.id
+    }
+
+    /// The NCBI name of the genetic code.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Translate `codon` to an amino acid.
+    ///
+    /// Stop codons give `*`.  Codons with IUPAC ambiguity codes give the amino acid
+    /// that all possible codons agree on, `X` otherwise.
+    pub fn translate_codon(&self, codon: &[u8; 3]) -> char {
+        let mut result = None;
+        for i in bases(codon[0]) {
+            for j in bases(codon[1]) {
+                for k in bases(codon[2]) {
+                    let amino_acid = self.amino_acids.as_bytes()[16 * i + 4 * j + k];
+                    match result {
+                        None => result = Some(amino_acid),
+                        Some(other) if other != amino_acid => return 'X',
+                        Some(_) => (),
+                    }
+                }
+            }
+        }
+        result.map(char::from).unwrap_or('X')
+    }
+
+    /// Whether `codon` is a start codon, ambiguous codons are not.
+    pub fn is_start(&self, codon: &[u8; 3]) -> bool {
+        match (bases(codon[0]), bases(codon[1]), bases(codon[2])) {
+            ([i], [j], [k]) => self.starts.as_bytes()[16 * i + 4 * j + k] == b'M',
+            _ => false,
+        }
+    }
+}
+
+impl Default for GeneticCode {
+    fn default() -> Self {
+        Self::STANDARD
+    }
+}
+
+/// Indices of the bases that the nucleotide `base` stands for, in `TCAG` order.
+fn bases(base: u8) -> &'static [usize] {
+    match base.to_ascii_uppercase() {
+        b'T' | b'U' => &[0],
+        b'C' => &[1],
+        b'A' => &[2],
+        b'G' => &[3],
+        b'R' => &[2, 3],
+        b'Y' => &[0, 1],
+        b'S' => &[1, 3],
+        b'W' => &[0, 2],
+        b'K' => &[0, 3],
+        b'M' => &[1, 2],
+        b'B' => &[0, 1, 3],
+        b'D' => &[0, 2, 3],
+        b'H' => &[0, 1, 2],
+        b'V' => &[1, 2, 3],
+        b'N' => &[0, 1, 2, 3],
+        _ => &[],
+    }
+}
+
+/// Handling of stop codons in `translate()`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StopHandling {
+    /// Translate stop codons to `*` and continue.
+    #[default]
+    Keep,
+    /// End the translation before the first stop codon.
+    Truncate,
+    /// Fail on stop codons other than the last codon, which is translated to `*`.
+    Error,
+}
+
+/// Handling of an incomplete codon at the end of the sequence in `translate()`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PartialCodonHandling {
+    /// Ignore the incomplete codon.
+    #[default]
+    Ignore,
+    /// Translate the incomplete codon to `X`.
+    Translate,
+    /// Fail on the incomplete codon.
+    Error,
+}
+
+/// Options for `translate()`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TranslateOptions {
+    /// The genetic code to use.
+    pub genetic_code: GeneticCode,
+    /// Reading frame, i.e., the offset of the first codon in the sequence.
+    pub frame: usize,
+    /// Handling of stop codons.
+    pub stop: StopHandling,
+    /// Handling of an incomplete codon at the end.
+    pub partial_codon: PartialCodonHandling,
+}
+
+/// Translate the nucleotide sequence `seq` to a protein sequence.
+pub fn translate(seq: &str, options: &TranslateOptions) -> Result<String, Error> {
+    let seq = seq.as_bytes().get(options.frame..).unwrap_or_default();
+    let codons = seq.chunks_exact(3);
+    let remainder = codons.remainder();
+    let count = codons.len();
+
+    let mut result = String::with_capacity(count + 1);
+    for (i, codon) in codons.enumerate() {
+        let amino_acid = options
+            .genetic_code
+            .translate_codon(codon.try_into().expect("codons have three bases"));
+        if amino_acid == '*' {
+            match options.stop {
+                StopHandling::Keep => (),
+                StopHandling::Truncate => return Ok(result),
+                StopHandling::Error => {
+                    if i + 1 < count {
+                        return Err(Error::TranslationStopCodon(i));
+                    }
+                }
+            }
+        }
+        result.push(amino_acid);
+    }
+
+    if !remainder.is_empty() {
+        match options.partial_codon {
+            PartialCodonHandling::Ignore => (),
+            PartialCodonHandling::Translate => result.push('X'),
+            PartialCodonHandling::Error => {
+                return Err(Error::TranslationPartialCodon(remainder.len()))
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::{translate, GeneticCode, PartialCodonHandling, StopHandling, TranslateOptions};
+
+    #[test]
+    fn genetic_codes() -> Result<(), anyhow::Error> {
+        for code in GeneticCode::all() {
+            assert_eq!(code.amino_acids.len(), 64, "{}", code.name());
+            assert_eq!(code.starts.len(), 64, "{}", code.name());
+            assert_eq!(GeneticCode::from_id(code.id())?, *code);
+        }
+        assert!(GeneticCode::from_id(7).is_err());
+        assert_eq!(GeneticCode::default(), GeneticCode::STANDARD);
+
+        let mito = GeneticCode::from_id(2)?;
+        assert_eq!(mito.name(), "Vertebrate Mitochondrial");
+        assert_eq!(mito.translate_codon(b"TGA"), 'W');
+        assert_eq!(mito.translate_codon(b"AGA"), '*');
+        assert_eq!(GeneticCode::STANDARD.translate_codon(b"TGA"), '*');
+        assert_eq!(GeneticCode::STANDARD.translate_codon(b"AGA"), 'R');
+
+        assert!(GeneticCode::STANDARD.is_start(b"ATG"));
+        assert!(GeneticCode::STANDARD.is_start(b"CTG"));
+        assert!(!GeneticCode::STANDARD.is_start(b"ATA"));
+        assert!(mito.is_start(b"ATA"));
+        assert!(!mito.is_start(b"ATN"));
+
+        let ids: Vec<u8> = GeneticCode::all().iter().map(GeneticCode::id).collect();
+        assert_eq!(
+            ids,
+            vec![
+                1, 2, 3, 4, 5, 6, 9, 10, 11, 12, 13, 14, 16, 21, 22, 23, 24, 25, 26, 27, 28, 29,
+                30, 31, 33
+            ]
+        );
+        assert_eq!(GeneticCode::from_id(26)?.translate_codon(b"CTG"), 'A');
+        assert_eq!(GeneticCode::from_id(29)?.translate_codon(b"TAA"), 'Y');
+        assert_eq!(GeneticCode::from_id(31)?.translate_codon(b"TAG"), 'E');
+        assert_eq!(GeneticCode::from_id(33)?.translate_codon(b"AGG"), 'K');
+        assert!(GeneticCode::from_id(32).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn translate_codon_ambiguous() {
+        let code = GeneticCode::STANDARD;
+        assert_eq!(code.translate_codon(b"aug"), 'M');
+        assert_eq!(code.translate_codon(b"GCN"), 'A');
+        assert_eq!(code.translate_codon(b"TAR"), '*');
+        assert_eq!(code.translate_codon(b"MGR"), 'R');
+        assert_eq!(code.translate_codon(b"ATN"), 'X');
+        assert_eq!(code.translate_codon(b"A-G"), 'X');
+    }
+
+    #[test]
+    fn translate_frames() -> Result<(), anyhow::Error> {
+        let seq = "ATGGCCTAAGGG";
+        let options = |frame| TranslateOptions {
+            frame,
+            ..Default::default()
+        };
+
+        assert_eq!(translate(seq, &options(0))?, "MA*G");
+        assert_eq!(translate(seq, &options(1))?, "WPK");
+        assert_eq!(translate(seq, &options(2))?, "GLR");
+        assert_eq!(translate(seq, &options(20))?, "");
+
+        Ok(())
+    }
+
+    #[test]
+    fn translate_stops() -> Result<(), anyhow::Error> {
+        let options = |stop| TranslateOptions {
+            stop,
+            ..Default::default()
+        };
+
+        assert_eq!(translate("ATGTAAGCC", &options(StopHandling::Keep))?, "M*A");
+        assert_eq!(
+            translate("ATGTAAGCC", &options(StopHandling::Truncate))?,
+            "M"
+        );
+        assert_eq!(
+            translate("ATGTAAGCC", &options(StopHandling::Error))
+                .unwrap_err()
+                .to_string(),
+            "stop codon at codon 1 before the end of the translation"
+        );
+        assert_eq!(
+            translate("ATGGCCTAA", &options(StopHandling::Error))?,
+            "MA*"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn translate_partial_codons() -> Result<(), anyhow::Error> {
+        let options = |partial_codon| TranslateOptions {
+            partial_codon,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            translate("ATGGC", &options(PartialCodonHandling::Ignore))?,
+            "M"
+        );
+        assert_eq!(
+            translate("ATGGC", &options(PartialCodonHandling::Translate))?,
+            "MX"
+        );
+        assert!(translate("ATGGC", &options(PartialCodonHandling::Error)).is_err());
+        assert_eq!(
+            translate("ATGGCC", &options(PartialCodonHandling::Error))?,
+            "MA"
+        );
+
+        Ok(())
+    }
+}
+
+// <LICENSE>
+// Copyright 2023 seqrepo-rs Contributors
+// Copyright 2016 biocommons.seqrepo Contributors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+// </LICENSE>
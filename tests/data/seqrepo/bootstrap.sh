@@ -39,22 +39,20 @@ INSTANCE=$2
 # Destination directory.
 DST=$SCRIPT_DIR
 
+# Command line interface of seqrepo-rs, built before anything is removed.
+cargo build --quiet --manifest-path $SCRIPT_DIR/../../../Cargo.toml --features cli --bin seqrepo
+SEQREPO=$(readlink -f $SCRIPT_DIR/../../../target/debug/seqrepo)
+
 # Import SQLite database ----------------------------------------------------
 
 rm -rf $DST
 
-seqrepo --root-directory $DST init --instance-name latest
+$SEQREPO --root-directory $DST init --instance-name latest
 
 tx=NM_001304430.2  # hash="5q5HZTCRudL17NTiv5Bn6th__0FrZH04"
 
-echo ">$tx" \
+$SEQREPO --root-directory $SRC export --instance-name $INSTANCE --namespace refseq $tx \
+| sed -e 's/^>NCBI:/>/' \
 > $DST/$tx.fasta
 
-echo "sr[\"$tx\"][:]" \
-| seqrepo --root-directory $SRC start-shell -i $INSTANCE \
-| grep Out \
-| awk '{ print $NF }' \
-| tr -d "'" \
->> $DST/$tx.fasta
-
-seqrepo --root-directory $DST load --instance-name latest --namespace refseq $DST/$tx.fasta
+$SEQREPO --root-directory $DST load --instance-name latest --namespace refseq $DST/$tx.fasta